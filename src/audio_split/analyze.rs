@@ -1,20 +1,170 @@
-use std::{path::PathBuf, time::Duration};
+use std::{
+    fmt,
+    fs::File,
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
 
-use tokio::process::Command;
+use iced::{
+    futures::{SinkExt, Stream, channel::mpsc},
+    task,
+};
+use rodio::Source;
+use tokio::{
+    io::{AsyncBufReadExt, BufReader},
+    process::Command,
+};
 
-use crate::audio_split::error::Error;
+use crate::audio_split::{error::Error, loudness::CLIP_LEVEL};
 
-pub async fn detect_silence(
-    path: impl Into<PathBuf> + Send + 'static,
+// silence length that scores about two thirds
+const SCORE_LENGTH: Duration = Duration::from_secs(1);
+// depth below the threshold that scores full
+const SCORE_DEPTH_DB: f32 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Silence {
+    start: Duration,
+    end: Duration,
+    // mean level below the threshold in dB, `None` when it couldn't be measured
+    depth: Option<f32>,
+}
+
+impl Silence {
+    pub fn new(start: Duration, end: Duration) -> Self {
+        Self {
+            start,
+            end,
+            depth: None,
+        }
+    }
+    pub fn with_depth(self, depth: f32) -> Self {
+        Self {
+            depth: Some(depth),
+            ..self
+        }
+    }
+    // confidence of the split point in the middle, from 0 to 1
+    pub fn score(&self) -> f32 {
+        let length = (self.end - self.start).as_secs_f32() / SCORE_LENGTH.as_secs_f32();
+        let length_score = 1.0 - (-length).exp();
+        match self.depth {
+            Some(depth) => (length_score + (depth / SCORE_DEPTH_DB).clamp(0.0, 1.0)) / 2.0,
+            None => length_score,
+        }
+    }
+    pub fn start(&self) -> Duration {
+        self.start
+    }
+    pub fn end(&self) -> Duration {
+        self.end
+    }
+    // split points are placed in the middle of a silence
+    pub fn midpoint(&self) -> Duration {
+        (self.start + self.end) / 2
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum AnalysisEvent {
+    // position ffmpeg has decoded up to
+    Progress(Duration),
+    Found(Silence),
+    // all detected silences, depths are added afterwards from the level envelope
+    Finished(Result<Vec<Silence>, Error>),
+}
+
+// running analysis, dropping the stream of `analyze_silence` kills ffmpeg
+#[derive(Debug)]
+pub struct Analysis {
+    handle: task::Handle,
+    range: Option<(Duration, Duration)>,
+    position: Duration,
+    end: Duration,
+    found: usize,
+}
+
+impl Analysis {
+    pub fn new(handle: task::Handle, range: Option<(Duration, Duration)>, end: Duration) -> Self {
+        Self {
+            handle,
+            range,
+            position: range.map(|(start, _)| start).unwrap_or_default(),
+            end,
+            found: 0,
+        }
+    }
+    pub fn range(&self) -> Option<(Duration, Duration)> {
+        self.range
+    }
+    pub fn set_position(&mut self, position: Duration) {
+        self.position = position.min(self.end);
+    }
+    pub fn add_found(&mut self) {
+        self.found += 1;
+    }
+    pub fn cancel(self) {
+        self.handle.abort();
+    }
+}
+
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "analyzing {:.1} / {:.1} s, {} found",
+            self.position.as_secs_f32(),
+            self.end.as_secs_f32(),
+            self.found
+        )
+    }
+}
+
+// `range` limits the analysis to a part of the file, the silences keep absolute timestamps
+pub fn analyze_silence(
+    path: PathBuf,
+    threshold_db: f32,
+    min_silence_duration: Duration,
+    range: Option<(Duration, Duration)>,
+) -> impl Stream<Item = AnalysisEvent> {
+    iced::stream::channel(100, async move |mut output| {
+        let silences = detect_silence(
+            &path,
+            threshold_db,
+            min_silence_duration,
+            range,
+            &mut output,
+        )
+        .await;
+        let _ = output.send(AnalysisEvent::Finished(silences)).await;
+    })
+}
+
+async fn detect_silence(
+    path: &Path,
     threshold_db: f32,
     min_silence_duration: Duration,
-) -> Result<Vec<Duration>, Error> {
-    let path: PathBuf = path.into();
-    let mut time_stamps = Vec::new();
+    range: Option<(Duration, Duration)>,
+    output: &mut mpsc::Sender<AnalysisEvent>,
+) -> Result<Vec<Silence>, Error> {
+    let mut silences = Vec::new();
 
-    let output = Command::new("ffmpeg")
+    let mut command = Command::new("ffmpeg");
+    command
         .arg("-hide_banner")
         .arg("-nostats")
+        .arg("-progress")
+        .arg("pipe:2");
+    if let Some((start, end)) = range {
+        command
+            .arg("-ss")
+            .arg(format!("{:.6}", start.as_secs_f64()))
+            .arg("-to")
+            .arg(format!("{:.6}", end.as_secs_f64()));
+    }
+    let offset = range.map(|(start, _)| start).unwrap_or_default();
+    let mut child = command
         .arg("-i")
         .arg(path)
         .arg("-af")
@@ -26,30 +176,44 @@ pub async fn detect_silence(
         .arg("-f")
         .arg("null")
         .arg("-")
-        .output()
-        .await
-        .unwrap();
-    if !output.status.success() {
-        panic!("{output:?}")
-    }
-    let stderr = String::from_utf8_lossy(&output.stderr);
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    let mut lines = BufReader::new(child.stderr.take().expect("stderr is piped")).lines();
     let mut start_time: Option<f64> = None;
-    for line in stderr.lines() {
+    while let Some(line) = lines.next_line().await? {
+        if let Some(us) = line
+            .strip_prefix("out_time_us=")
+            .and_then(|us| us.trim().parse().ok())
+        {
+            let _ = output
+                .send(AnalysisEvent::Progress(offset + Duration::from_micros(us)))
+                .await;
+            continue;
+        }
         if !line.contains("silencedetect") {
             continue;
         }
-        if let Some(t) = parse_secs_from_line(line, "silence_start:") {
+        if let Some(t) = parse_secs_from_line(&line, "silence_start:") {
             start_time = Some(t);
-        } else if let Some(t) = parse_secs_from_line(line, "silence_end:")
+        } else if let Some(t) = parse_secs_from_line(&line, "silence_end:")
             && let Some(start) = start_time
         {
-            let time = (start + t) / 2.;
-            time_stamps.push(Duration::from_secs_f64(time));
+            let silence = Silence::new(
+                offset + Duration::from_secs_f64(start.max(0.0)),
+                offset + Duration::from_secs_f64(t),
+            );
+            silences.push(silence);
+            let _ = output.send(AnalysisEvent::Found(silence)).await;
         }
     }
-    println!("{time_stamps:#?}");
+    let status = child.wait().await?;
+    if !status.success() {
+        return Err(std::io::Error::other(format!("ffmpeg failed with {status}")).into());
+    }
 
-    Ok(time_stamps)
+    Ok(silences)
 }
 
 fn parse_secs_from_line(line: &str, key: &str) -> Option<f64> {
@@ -58,3 +222,464 @@ fn parse_secs_from_line(line: &str, key: &str) -> Option<f64> {
     let num = rest.split('|').next()?.trim();
     num.parse::<f64>().ok()
 }
+
+// consecutive full scale frames reported as clipping
+const MIN_CLIPPED_FRAMES: u64 = 3;
+const MIN_DROPOUT: Duration = Duration::from_millis(5);
+// a dropout starts abruptly, digital silence after a fade out is not reported
+const DROPOUT_EDGE: f32 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefectKind {
+    Clipping,
+    Dropout,
+}
+
+impl fmt::Display for DefectKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DefectKind::Clipping => write!(f, "clipping"),
+            DefectKind::Dropout => write!(f, "dropout"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Defect {
+    kind: DefectKind,
+    start: Duration,
+    end: Duration,
+}
+
+impl Defect {
+    pub fn kind(&self) -> DefectKind {
+        self.kind
+    }
+    pub fn start(&self) -> Duration {
+        self.start
+    }
+    pub fn end(&self) -> Duration {
+        self.end
+    }
+}
+
+// runs over the frames of the decoded audio, one frame holds a sample per channel
+pub struct DefectDetector {
+    sample_rate: u32,
+    frame: u64,
+    clipped_since: Option<u64>,
+    zero_since: Option<u64>,
+    abrupt_zero: bool,
+    last_peak: f32,
+    defects: Vec<Defect>,
+}
+
+impl DefectDetector {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            frame: 0,
+            clipped_since: None,
+            zero_since: None,
+            abrupt_zero: false,
+            last_peak: 0.0,
+            defects: Vec::new(),
+        }
+    }
+    pub fn push_frame(&mut self, frame: &[f32]) {
+        let peak = frame.iter().fold(0.0f32, |p, s| p.max(s.abs()));
+        if peak >= CLIP_LEVEL {
+            self.clipped_since.get_or_insert(self.frame);
+        } else {
+            self.finish_clipping();
+        }
+        if peak == 0.0 {
+            if self.zero_since.is_none() {
+                self.zero_since = Some(self.frame);
+                self.abrupt_zero = self.last_peak >= DROPOUT_EDGE;
+            }
+        } else if let Some(since) = self.zero_since.take()
+            && self.abrupt_zero
+            && self.time(self.frame - since) >= MIN_DROPOUT
+        {
+            self.push(DefectKind::Dropout, since);
+        }
+        self.last_peak = peak;
+        self.frame += 1;
+    }
+    // digital silence at the end of the file is padding, not a dropout
+    pub fn finish(mut self) -> Vec<Defect> {
+        self.finish_clipping();
+        self.defects
+    }
+    fn finish_clipping(&mut self) {
+        if let Some(since) = self.clipped_since.take()
+            && self.frame - since >= MIN_CLIPPED_FRAMES
+        {
+            self.push(DefectKind::Clipping, since);
+        }
+    }
+    fn push(&mut self, kind: DefectKind, since: u64) {
+        self.defects.push(Defect {
+            kind,
+            start: self.time(since),
+            end: self.time(self.frame),
+        });
+    }
+    fn time(&self, frames: u64) -> Duration {
+        Duration::from_secs_f64(frames as f64 / self.sample_rate as f64)
+    }
+}
+
+pub async fn detect_defects(
+    path: impl Into<PathBuf> + Send + 'static,
+) -> Result<Vec<Defect>, Error> {
+    tokio::task::spawn_blocking(|| {
+        let decoder = rodio::Decoder::try_from(File::open(path.into())?)?;
+        let channels = decoder.channels().get() as usize;
+        let mut detector = DefectDetector::new(decoder.sample_rate().get());
+        let mut frame = Vec::with_capacity(channels);
+        for sample in decoder {
+            frame.push(sample);
+            if frame.len() == channels {
+                detector.push_frame(&frame);
+                frame.clear();
+            }
+        }
+        Ok(detector.finish())
+    })
+    .await
+    .unwrap()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SegmentLength {
+    pub target: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+// walks through the audio and cuts each segment at the silence closest to the target
+// length, longer silences win over slightly closer ones
+pub fn target_length_cuts(
+    silences: &[Silence],
+    duration: Duration,
+    length: &SegmentLength,
+) -> Vec<Duration> {
+    let mut cuts = Vec::new();
+    let mut pos = Duration::ZERO;
+    while duration.saturating_sub(pos) > length.max {
+        let ideal = pos + length.target;
+        let cost = |s: &Silence| {
+            s.midpoint().abs_diff(ideal).as_secs_f64() / (1.0 + (s.end - s.start).as_secs_f64())
+        };
+        let in_range: Vec<&Silence> = silences
+            .iter()
+            .filter(|s| {
+                s.midpoint() > pos
+                    && pos + length.min <= s.midpoint()
+                    && s.midpoint() <= pos + length.max
+            })
+            .collect();
+        // without a silence in range the segment gets too long or too short
+        let candidates = if in_range.is_empty() {
+            silences.iter().filter(|s| s.midpoint() > pos).collect()
+        } else {
+            in_range
+        };
+        let Some(best) = candidates
+            .into_iter()
+            .min_by(|a, b| cost(a).total_cmp(&cost(b)))
+        else {
+            break;
+        };
+        pos = best.midpoint();
+        cuts.push(pos);
+    }
+    cuts
+}
+
+// applied to the detected silences before they become split points
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Constraints {
+    pub min_segment_length: Option<Duration>,
+    pub max_segments: Option<usize>,
+}
+
+impl Constraints {
+    // drops the weakest, that is shortest, silences first
+    pub fn apply(&self, mut silences: Vec<Silence>, duration: Duration) -> Vec<Silence> {
+        let strength = |s: &Silence| s.end - s.start;
+        if let Some(min) = self.min_segment_length {
+            loop {
+                let mut bounds = vec![Duration::ZERO];
+                bounds.extend(silences.iter().map(Silence::midpoint));
+                bounds.push(duration);
+                let too_short = |i: usize| bounds[i + 1].saturating_sub(bounds[i]) < min;
+                let Some(weakest) = (0..silences.len())
+                    .filter(|&i| too_short(i) || too_short(i + 1))
+                    .min_by_key(|&i| strength(&silences[i]))
+                else {
+                    break;
+                };
+                silences.remove(weakest);
+            }
+        }
+        if let Some(max) = self.max_segments {
+            while silences.len() >= max.max(1) {
+                let weakest = (0..silences.len())
+                    .min_by_key(|&i| strength(&silences[i]))
+                    .unwrap();
+                silences.remove(weakest);
+            }
+        }
+        silences
+    }
+}
+
+// where the analysis puts split points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SplitMode {
+    #[default]
+    Silence,
+    Interval,
+    EqualParts,
+    // every interval, moved to the nearest silence within a window
+    SnappedInterval,
+    // changes of the spectrum, for mixes without silences between tracks
+    TrackBoundaries,
+    // beeps or DTMF digits put into the recording on purpose
+    ToneMarkers,
+}
+
+impl SplitMode {
+    pub const ALL: [SplitMode; 6] = [
+        SplitMode::Silence,
+        SplitMode::Interval,
+        SplitMode::EqualParts,
+        SplitMode::SnappedInterval,
+        SplitMode::TrackBoundaries,
+        SplitMode::ToneMarkers,
+    ];
+    pub fn config_name(self) -> &'static str {
+        match self {
+            SplitMode::Silence => "silence",
+            SplitMode::Interval => "interval",
+            SplitMode::EqualParts => "parts",
+            SplitMode::SnappedInterval => "snapped_interval",
+            SplitMode::TrackBoundaries => "tracks",
+            SplitMode::ToneMarkers => "tones",
+        }
+    }
+    pub fn from_config_name(name: &str) -> Option<Self> {
+        SplitMode::ALL.into_iter().find(|m| m.config_name() == name)
+    }
+}
+
+impl fmt::Display for SplitMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SplitMode::Silence => write!(f, "at silences"),
+            SplitMode::Interval => write!(f, "every interval"),
+            SplitMode::EqualParts => write!(f, "equal parts"),
+            SplitMode::SnappedInterval => write!(f, "interval near silence"),
+            SplitMode::TrackBoundaries => write!(f, "track boundaries"),
+            SplitMode::ToneMarkers => write!(f, "at tone markers"),
+        }
+    }
+}
+
+pub fn interval_points(duration: Duration, interval: Duration) -> Vec<Duration> {
+    if interval.is_zero() {
+        return Vec::new();
+    }
+    (1..)
+        .map(|k| interval * k)
+        .take_while(|p| *p < duration)
+        .collect()
+}
+
+pub fn equal_parts(duration: Duration, parts: u32) -> Vec<Duration> {
+    (1..parts).map(|k| duration * k / parts).collect()
+}
+
+// points without a silence in the window stay where they are
+pub fn snap_to_silences(
+    points: &[Duration],
+    silences: &[Silence],
+    window: Duration,
+) -> Vec<Duration> {
+    let mut snapped: Vec<Duration> = points
+        .iter()
+        .map(|p| {
+            silences
+                .iter()
+                .map(Silence::midpoint)
+                .filter(|m| m.abs_diff(*p) <= window)
+                .min_by_key(|m| m.abs_diff(*p))
+                .unwrap_or(*p)
+        })
+        .collect();
+    snapped.dedup();
+    snapped
+}
+
+// length of the windows the level envelope is measured in, rounded to whole frames
+pub const LEVEL_WINDOW: Duration = Duration::from_millis(50);
+// windows below are digital silence and left out of the histogram
+const HISTOGRAM_FLOOR_DB: f32 = -100.0;
+const NOISE_PERCENTILE: f32 = 0.1;
+const SIGNAL_PERCENTILE: f32 = 0.9;
+// threshold position between noise floor and signal level
+const THRESHOLD_POSITION: f32 = 0.25;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoiseFloor {
+    pub floor: f32,
+    pub threshold: f32,
+}
+
+// RMS levels in dB of consecutive windows of the downmixed audio
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Envelope {
+    levels: Vec<f32>,
+    // the real length of a window, `LEVEL_WINDOW` at the sample rate of the file
+    window: Duration,
+}
+
+impl Envelope {
+    pub fn new(levels: Vec<f32>, window: Duration) -> Self {
+        Self { levels, window }
+    }
+    pub fn levels(&self) -> &[f32] {
+        &self.levels
+    }
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+    pub fn level_at(&self, position: Duration) -> Option<f32> {
+        self.levels.get(self.index(position)).copied()
+    }
+    fn index(&self, position: Duration) -> usize {
+        (position.as_secs_f64() / self.window.as_secs_f64()) as usize
+    }
+    fn time(&self, index: usize) -> Duration {
+        self.window * index as u32
+    }
+}
+
+pub async fn level_envelope(path: impl Into<PathBuf> + Send + 'static) -> Result<Envelope, Error> {
+    tokio::task::spawn_blocking(|| {
+        let decoder = rodio::Decoder::try_from(File::open(path.into())?)?;
+        let channels = decoder.channels().get() as u64;
+        let rate = decoder.sample_rate().get() as f64;
+        let frames = (LEVEL_WINDOW.as_secs_f64() * rate).round().max(1.0);
+        let window = frames as u64 * channels;
+        let mut levels = Vec::new();
+        let (mut sum_of_squares, mut count) = (0.0f64, 0u64);
+        for sample in decoder {
+            sum_of_squares += (sample as f64).powi(2);
+            count += 1;
+            if count == window {
+                levels.push((10.0 * (sum_of_squares / count as f64).log10()) as f32);
+                (sum_of_squares, count) = (0.0, 0);
+            }
+        }
+        Ok(Envelope::new(
+            levels,
+            Duration::from_secs_f64(frames / rate),
+        ))
+    })
+    .await
+    .unwrap()
+}
+
+// silences in a cached `level_envelope`, a quick approximation of `silencedetect`
+pub fn envelope_silences(
+    envelope: &Envelope,
+    threshold_db: f32,
+    min_silence_duration: Duration,
+) -> Vec<Silence> {
+    let mut silences = Vec::new();
+    let mut start = None;
+    for (index, level) in envelope.levels.iter().chain([&f32::INFINITY]).enumerate() {
+        match (start, *level < threshold_db) {
+            (None, true) => start = Some(index),
+            (Some(first), false) => {
+                let silence = Silence::new(envelope.time(first), envelope.time(index));
+                if silence.end - silence.start >= min_silence_duration {
+                    silences.push(match silence_depth(envelope, &silence, threshold_db) {
+                        Some(depth) => silence.with_depth(depth),
+                        None => silence,
+                    });
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    silences
+}
+
+// `None` when the audio is digital silence only
+pub fn estimate_noise_floor(levels: &[f32]) -> Option<NoiseFloor> {
+    let mut histogram = [0usize; 100];
+    for level in levels.iter().filter(|l| **l >= HISTOGRAM_FLOOR_DB) {
+        let bin = ((level - HISTOGRAM_FLOOR_DB) as usize).min(histogram.len() - 1);
+        histogram[bin] += 1;
+    }
+    let total: usize = histogram.iter().sum();
+    if total == 0 {
+        return None;
+    }
+    let percentile = |p: f32| {
+        let target = ((total as f32 * p).ceil() as usize).max(1);
+        let mut seen = 0;
+        let bin = histogram
+            .iter()
+            .position(|c| {
+                seen += c;
+                seen >= target
+            })
+            .unwrap();
+        HISTOGRAM_FLOOR_DB + bin as f32 + 0.5
+    };
+    let floor = percentile(NOISE_PERCENTILE);
+    let signal = percentile(SIGNAL_PERCENTILE);
+    Some(NoiseFloor {
+        floor,
+        threshold: floor + (signal - floor) * THRESHOLD_POSITION,
+    })
+}
+
+// measures how far each silence lies below the threshold, keeps the silences as they
+// are while the envelope is not measured
+pub fn measure_depths(
+    envelope: &Envelope,
+    silences: Vec<Silence>,
+    threshold_db: f32,
+) -> Vec<Silence> {
+    silences
+        .into_iter()
+        .map(|s| match silence_depth(envelope, &s, threshold_db) {
+            Some(depth) => s.with_depth(depth),
+            None => s,
+        })
+        .collect()
+}
+
+fn silence_depth(envelope: &Envelope, silence: &Silence, threshold_db: f32) -> Option<f32> {
+    let levels = &envelope.levels;
+    let inside =
+        levels.get(envelope.index(silence.start)..envelope.index(silence.end).min(levels.len()))?;
+    if inside.is_empty() {
+        return None;
+    }
+    let finite: Vec<f32> = inside.iter().copied().filter(|l| l.is_finite()).collect();
+    if finite.is_empty() {
+        // digital silence
+        return Some(SCORE_DEPTH_DB);
+    }
+    let mean = finite.iter().sum::<f32>() / finite.len() as f32;
+    Some((threshold_db - mean).max(0.0))
+}
@@ -2,7 +2,13 @@ use std::{fmt, sync::Arc, time::Duration};
 
 use iced::{Element, Length, widget};
 
-use super::{Message, audio_span::AudioSpan};
+use super::{
+    Message,
+    analyze::{Defect, Envelope, Silence},
+    audio_span::AudioSpan,
+    classify::ClassRegion,
+    loudness::Levels,
+};
 
 #[derive(Clone)]
 pub struct Audio {
@@ -11,6 +17,15 @@ pub struct Audio {
     length: AudioSpan,
     file_name: String,
     index_counter: u32,
+    silences: Vec<Silence>,
+    defects: Vec<Defect>,
+    // measured ranges, a span shows the levels of its exact range
+    levels: Vec<(Duration, Duration, Levels)>,
+    scores: Vec<(Duration, f32)>,
+    // `level_envelope` of the file, empty until it is measured
+    envelope: Arc<Envelope>,
+    threshold: Option<f32>,
+    regions: Vec<ClassRegion>,
 }
 
 impl Audio {
@@ -21,12 +36,22 @@ impl Audio {
             spans: vec![span],
             file_name,
             index_counter: 0,
+            silences: Vec::new(),
+            defects: Vec::new(),
+            levels: Vec::new(),
+            scores: Vec::new(),
+            envelope: Arc::default(),
+            threshold: None,
+            regions: Vec::new(),
         }
     }
-    pub fn view(&self) -> Element<'_, Message> {
+    pub fn view(&self, looped_span: Option<u32>, selected_spans: &[u32]) -> Element<'_, Message> {
         let mut row = widget::Row::new();
         for span in &self.spans {
-            row = row.push(span.view());
+            row = row.push(span.view(
+                looped_span == Some(span.id()),
+                selected_spans.contains(&span.id()),
+            ));
         }
 
         widget::scrollable(row.padding(5))
@@ -40,6 +65,44 @@ impl Audio {
     pub fn spans_mut(&mut self) -> &mut [AudioSpan] {
         &mut self.spans
     }
+    pub fn duration(&self) -> Duration {
+        self.length.end()
+    }
+    pub fn span_at(&self, pos: Duration) -> Option<&AudioSpan> {
+        self.spans
+            .iter()
+            .find(|s| s.start() <= pos && pos < s.end())
+    }
+    pub fn all_split_points(&self) -> Vec<Duration> {
+        let mut split_points: Vec<Duration> = self
+            .spans
+            .iter()
+            .filter(|s| !s.is_excluded())
+            .flat_map(|s| s.split_points().iter())
+            .copied()
+            .collect();
+        split_points.sort();
+        split_points
+    }
+    // playback wraps around at the end of the last span that is not excluded
+    pub fn playback_end(&self) -> Option<Duration> {
+        self.spans
+            .iter()
+            .rev()
+            .find(|s| !s.is_excluded())
+            .map(|s| s.end())
+    }
+    pub fn selected_split_points(&self) -> Vec<Duration> {
+        let mut selected: Vec<Duration> = self
+            .spans
+            .iter()
+            .filter(|s| !s.is_excluded())
+            .flat_map(|s| s.selected_split_points().iter())
+            .copied()
+            .collect();
+        selected.sort();
+        selected
+    }
     pub fn get_pos(&self) -> Duration {
         self.player.get_pos()
     }
@@ -50,12 +113,22 @@ impl Audio {
             .map(|s| s.set_pos_and_get_info(pos));
         self.player.try_seek(Duration::from_secs_f32(pos)).unwrap();
     }
+    pub fn seek(&mut self, pos: Duration) {
+        self.player.try_seek(pos).unwrap();
+        self.update_position_info();
+    }
     pub fn update_position_info(&mut self) {
         let pos = self.player.get_pos().as_secs_f32();
         let mut found_zero = false;
         let mut found_next = false;
         let mut skip_to = Duration::default();
-        for (b, pos) in self.spans.iter_mut().map(|s| s.set_pos_and_get_info(pos)) {
+        // excluded spans are gaps, playback skips to the next span
+        for (b, pos) in self
+            .spans
+            .iter_mut()
+            .filter(|s| !s.is_excluded())
+            .map(|s| s.set_pos_and_get_info(pos))
+        {
             if b == 0 || found_zero {
                 found_zero = true;
             } else if b == -1 && !found_next {
@@ -78,6 +151,7 @@ impl Audio {
         let mut splits: Vec<Duration> = self
             .spans
             .iter()
+            .filter(|s| !s.is_excluded())
             .flat_map(|s| s.selected_split_points().iter())
             .copied()
             .collect();
@@ -85,6 +159,7 @@ impl Audio {
         for split in &splits {
             self.split_at(*split);
         }
+        self.distribute_markers();
         splits.len()
     }
     fn split_at(&mut self, pos: Duration) {
@@ -116,12 +191,159 @@ impl Audio {
         self.spans.insert(index, span_1);
         self.spans.insert(index + 1, span_2);
     }
+    // moves the edges of a span; freed ranges become excluded spans and a span
+    // can only grow into excluded neighbours
+    pub fn trim_span(&mut self, id: u32, start: Duration, end: Duration) -> bool {
+        let Some(mut i) = self.spans.iter().position(|s| s.id() == id) else {
+            return false;
+        };
+        if self.spans[i].is_excluded() {
+            return false;
+        }
+        let (old_start, old_end) = (self.spans[i].start(), self.spans[i].end());
+        let has_excluded_previous = i > 0 && self.spans[i - 1].is_excluded();
+        let has_excluded_next = self.spans.get(i + 1).is_some_and(|s| s.is_excluded());
+        let min_start = if has_excluded_previous {
+            self.spans[i - 1].start()
+        } else {
+            old_start
+        };
+        let max_end = if has_excluded_next {
+            self.spans[i + 1].end()
+        } else {
+            old_end
+        };
+        let start = start.max(min_start);
+        let end = end.min(max_end);
+        if start >= end {
+            return false;
+        }
+        let split_points = self.all_split_points_including_excluded();
+
+        if end < old_end && has_excluded_next {
+            let next = &mut self.spans[i + 1];
+            next.set_range(end, next.end());
+        } else if end < old_end {
+            let gap = self.excluded_span(end, old_end);
+            self.spans.insert(i + 1, gap);
+        } else if end > old_end && end == max_end {
+            self.spans.remove(i + 1);
+        } else if end > old_end {
+            let next = &mut self.spans[i + 1];
+            next.set_range(end, next.end());
+        }
+
+        if start > old_start && has_excluded_previous {
+            let previous = &mut self.spans[i - 1];
+            previous.set_range(previous.start(), start);
+        } else if start > old_start {
+            let gap = self.excluded_span(old_start, start);
+            self.spans.insert(i, gap);
+            i += 1;
+        } else if start < old_start && start == min_start {
+            self.spans.remove(i - 1);
+            i -= 1;
+        } else if start < old_start {
+            let previous = &mut self.spans[i - 1];
+            previous.set_range(previous.start(), start);
+        }
+
+        self.spans[i].set_range(start, end);
+        Audio::set_split_points(&mut self.spans, split_points);
+        self.distribute_markers();
+        true
+    }
+    fn excluded_span(&mut self, start: Duration, end: Duration) -> AudioSpan {
+        self.index_counter += 1;
+        let mut span = AudioSpan::new(
+            self.index_counter,
+            start,
+            end,
+            format!("{}_{}", self.file_name, self.index_counter),
+        );
+        span.set_excluded(true);
+        span
+    }
+    fn all_split_points_including_excluded(&self) -> Vec<Duration> {
+        let mut split_points: Vec<Duration> = self
+            .spans
+            .iter()
+            .flat_map(|s| s.split_points().iter())
+            .copied()
+            .collect();
+        split_points.sort();
+        split_points
+    }
+    pub fn update_span_range_input(&mut self, id: u32, start: Option<String>, end: Option<String>) {
+        if let Some(span) = self.get_span_mut(id) {
+            if let Some(start) = start {
+                span.set_start_input(start);
+            }
+            if let Some(end) = end {
+                span.set_end_input(end);
+            }
+        }
+    }
     pub fn delete_span(&mut self, id: u32) {
-        let res = self.spans.iter().enumerate().find(|(_, s)| id == s.id());
-        if let Some((i, _)) = res {
-            self.spans.remove(i);
+        if let Some(span) = self.get_span_mut(id) {
+            span.set_excluded(true);
+        }
+    }
+    pub fn restore_span(&mut self, id: u32) {
+        if let Some(span) = self.get_span_mut(id) {
+            span.set_excluded(false);
+        }
+    }
+    // merges every run of directly adjacent spans among `ids`, returns the number of merges
+    pub fn merge_spans(&mut self, ids: &[u32]) -> usize {
+        let mut merged = 0;
+        let mut i = 0;
+        while i + 1 < self.spans.len() {
+            let (first, second) = (&self.spans[i], &self.spans[i + 1]);
+            if ids.contains(&first.id())
+                && ids.contains(&second.id())
+                && first.end() == second.start()
+                && first.is_excluded() == second.is_excluded()
+            {
+                let second = self.spans.remove(i + 1);
+                self.spans[i].absorb(second);
+                merged += 1;
+            } else {
+                i += 1;
+            }
+        }
+        self.distribute_markers();
+        merged
+    }
+    pub fn merge_with_next(&mut self, id: u32) -> usize {
+        match self.spans.iter().position(|s| s.id() == id) {
+            Some(i) if i + 1 < self.spans.len() => {
+                let next = self.spans[i + 1].id();
+                self.merge_spans(&[id, next])
+            }
+            _ => 0,
         }
     }
+    pub fn merge_with_previous(&mut self, id: u32) -> usize {
+        match self.spans.iter().position(|s| s.id() == id) {
+            Some(i) if i > 0 => {
+                let previous = self.spans[i - 1].id();
+                self.merge_spans(&[previous, id])
+            }
+            _ => 0,
+        }
+    }
+    pub fn set_levels(&mut self, levels: Vec<(u32, Levels)>) {
+        for (id, levels) in levels {
+            let Some(span) = self.spans.iter().find(|s| s.id() == id) else {
+                continue;
+            };
+            let (start, end) = (span.start(), span.end());
+            self.levels.retain(|(s, e, _)| (*s, *e) != (start, end));
+            self.levels.push((start, end, levels));
+        }
+        self.distribute_markers();
+    }
     pub fn update_span_text(&mut self, id: u32, text: String) {
         if let Some(span) = self.get_span_mut(id) {
             span.set_name(text);
@@ -141,6 +363,46 @@ impl Audio {
             }
         }
     }
+    pub fn insert_split_point(&mut self, split_point: Duration) {
+        let mut split_points = self.all_split_points_including_excluded();
+        if let Err(index) = split_points.binary_search(&split_point) {
+            split_points.insert(index, split_point);
+            Self::set_split_points(&mut self.spans, split_points);
+        }
+    }
+    // split points, silences and scores outside of `start..=end` are kept
+    pub fn replace_split_points_in(
+        &mut self,
+        start: Duration,
+        end: Duration,
+        silences: Vec<Silence>,
+    ) {
+        let outside = |p: &Duration| *p < start || end < *p;
+        let selected: Vec<Duration> = self
+            .spans
+            .iter()
+            .flat_map(|s| s.selected_split_points().iter())
+            .copied()
+            .filter(outside)
+            .collect();
+        let mut split_points: Vec<Duration> = self
+            .all_split_points_including_excluded()
+            .into_iter()
+            .filter(outside)
+            .chain(silences.iter().map(Silence::midpoint))
+            .collect();
+        split_points.sort();
+        Self::set_split_points(&mut self.spans, split_points);
+        self.set_selected_split_points(&selected);
+        self.silences.retain(|s| outside(&s.midpoint()));
+        self.silences.extend(silences.iter().copied());
+        self.silences.sort_by_key(|s| s.start());
+        self.scores.retain(|(p, _)| outside(p));
+        self.scores
+            .extend(silences.iter().map(|s| (s.midpoint(), s.score())));
+        self.scores.sort_by_key(|(p, _)| *p);
+        self.distribute_markers();
+    }
     pub fn toggle_selected_split_points(&mut self, split_point: Duration) {
         for span in self.spans.iter_mut() {
             if span.toggle_split_point_selection(split_point) {
@@ -148,15 +410,99 @@ impl Audio {
             }
         }
     }
+    // replaces the selection of every span
+    pub fn set_selected_split_points(&mut self, split_points: &[Duration]) {
+        for span in self.spans.iter_mut() {
+            span.set_selected_split_points(split_points);
+        }
+    }
     pub fn split_points_selected(&self) -> bool {
         self.spans
             .iter()
-            .find(|a| a.split_points_selected())
-            .is_some()
+            .any(|a| !a.is_excluded() && a.split_points_selected())
     }
     pub fn reset(&mut self) {
         self.spans = vec![self.length.clone()];
         self.index_counter = 0;
+        self.silences.clear();
+        self.scores.clear();
+        // defects, levels, envelope and classes belong to the file and survive the reset of an
+        // undo
+        self.distribute_markers();
+    }
+    pub fn silences(&self) -> &[Silence] {
+        &self.silences
+    }
+    pub fn set_silences(&mut self, silences: Vec<Silence>) {
+        self.silences = silences;
+    }
+    pub fn defects(&self) -> &[Defect] {
+        &self.defects
+    }
+    pub fn set_defects(&mut self, defects: Vec<Defect>) {
+        self.defects = defects;
+        self.distribute_markers();
+    }
+    pub fn scores(&self) -> &[(Duration, f32)] {
+        &self.scores
+    }
+    // scores of the split points, see `Silence::score`
+    pub fn set_scores(&mut self, scores: Vec<(Duration, f32)>) {
+        self.scores = scores;
+        self.distribute_markers();
+    }
+    pub fn regions(&self) -> &[ClassRegion] {
+        &self.regions
+    }
+    pub fn set_regions(&mut self, regions: Vec<ClassRegion>) {
+        self.regions = regions;
+        self.distribute_markers();
+    }
+    pub fn envelope(&self) -> &Envelope {
+        &self.envelope
+    }
+    pub fn set_envelope(&mut self, envelope: Arc<Envelope>) {
+        self.envelope = envelope;
+        self.distribute_markers();
+    }
+    // threshold line drawn over the envelope
+    pub fn set_threshold(&mut self, threshold: Option<f32>) {
+        self.threshold = threshold;
+        self.distribute_markers();
+    }
+    // every span keeps the defects and scores in its range, so its canvas can draw them
+    fn distribute_markers(&mut self) {
+        for span in self.spans.iter_mut() {
+            span.set_envelope(self.envelope.clone(), self.threshold);
+            let defects = self
+                .defects
+                .iter()
+                .filter(|d| d.start() < span.end() && span.start() < d.end())
+                .copied()
+                .collect();
+            span.set_defects(defects);
+            if let Some((_, _, levels)) = self
+                .levels
+                .iter()
+                .find(|(s, e, _)| (*s, *e) == (span.start(), span.end()))
+            {
+                span.set_levels(*levels);
+            }
+            let scores = self
+                .scores
+                .iter()
+                .filter(|(p, _)| span.contains(*p))
+                .copied()
+                .collect();
+            span.set_scores(scores);
+            let regions = self
+                .regions
+                .iter()
+                .filter(|r| r.start() < span.end() && span.start() < r.end())
+                .copied()
+                .collect();
+            span.set_regions(regions);
+        }
     }
     pub fn file_name(&self) -> &str {
         &self.file_name
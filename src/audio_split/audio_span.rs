@@ -1,8 +1,14 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use iced::{Element, Length, widget};
 
-use super::{Message, debug_id::DebugId};
+use super::{
+    Message,
+    analyze::{Defect, Envelope},
+    classify::ClassRegion,
+    debug_id::DebugId,
+    loudness::Levels,
+};
 
 #[derive(Debug, Clone)]
 pub struct AudioSpan {
@@ -13,6 +19,15 @@ pub struct AudioSpan {
     position: f32,
     split_points: Vec<Duration>,
     selected_split_points: Vec<Duration>,
+    excluded: bool,
+    start_input: String,
+    end_input: String,
+    levels: Option<Levels>,
+    defects: Vec<Defect>,
+    scores: Vec<(Duration, f32)>,
+    envelope: Arc<Envelope>,
+    threshold: Option<f32>,
+    regions: Vec<ClassRegion>,
 }
 
 impl AudioSpan {
@@ -25,9 +40,18 @@ impl AudioSpan {
             position: start.as_secs_f32(),
             split_points: Vec::new(),
             selected_split_points: Vec::new(),
+            excluded: false,
+            start_input: fmt_secs(start),
+            end_input: fmt_secs(end),
+            levels: None,
+            defects: Vec::new(),
+            scores: Vec::new(),
+            envelope: Arc::default(),
+            threshold: None,
+            regions: Vec::new(),
         }
     }
-    pub fn view(&self) -> Element<'_, Message> {
+    pub fn view(&self, is_looped: bool, is_selected: bool) -> Element<'_, Message> {
         widget::container(
             widget::column![
                 widget::container(widget::canvas(self).width(self.calc_slider_length()))
@@ -38,13 +62,85 @@ impl AudioSpan {
                     |p| Message::AudioSpanPositionUpdate(self.id, p)
                 ),
                 widget::text_input("", &self.name)
-                    .on_input(|t| Message::SpanTextUpdate(self.id, t)),
-                widget::container(
-                    widget::button("delete")
-                        .style(widget::button::danger)
-                        .on_press(Message::DeleteAudioSpan(self.id))
-                )
-                .id(DebugId::ButtonDelete(self.id))
+                    .on_input_maybe(
+                        (!self.excluded).then_some(|t| Message::SpanTextUpdate(self.id, t))
+                    )
+                    .id(DebugId::TextInputSpanName(self.id)),
+                widget::text(self.levels.map(|l| l.to_string()).unwrap_or_default())
+                    .size(12)
+                    .style(if self.levels.is_some_and(|l| l.is_clipping()) {
+                        widget::text::danger
+                    } else {
+                        widget::text::base
+                    }),
+                widget::row![
+                    widget::text_input("start", &self.start_input)
+                        .on_input_maybe(
+                            (!self.excluded)
+                                .then_some(|t| Message::UpdateSpanStartInput(self.id, t))
+                        )
+                        .on_submit(Message::SubmitSpanRange(self.id))
+                        .id(DebugId::TextInputSpanStart(self.id)),
+                    widget::text_input("end", &self.end_input)
+                        .on_input_maybe(
+                            (!self.excluded).then_some(|t| Message::UpdateSpanEndInput(self.id, t))
+                        )
+                        .on_submit(Message::SubmitSpanRange(self.id))
+                        .id(DebugId::TextInputSpanEnd(self.id)),
+                ]
+                .spacing(5),
+                widget::row![
+                    widget::container(
+                        widget::button("loop")
+                            .style(if is_looped {
+                                widget::button::success
+                            } else {
+                                widget::button::secondary
+                            })
+                            .on_press_maybe(
+                                (!self.excluded).then_some(Message::ToggleSpanLoop(self.id))
+                            )
+                    )
+                    .id(DebugId::ButtonLoop(self.id)),
+                    if self.excluded {
+                        widget::container(
+                            widget::button("restore").on_press(Message::RestoreAudioSpan(self.id)),
+                        )
+                        .id(DebugId::ButtonRestore(self.id))
+                    } else {
+                        widget::container(
+                            widget::button("delete")
+                                .style(widget::button::danger)
+                                .on_press(Message::DeleteAudioSpan(self.id)),
+                        )
+                        .id(DebugId::ButtonDelete(self.id))
+                    },
+                    widget::container(
+                        widget::button("merge prev")
+                            .style(widget::button::secondary)
+                            .on_press(Message::MergeWithPrevious(self.id))
+                    )
+                    .id(DebugId::ButtonMergePrevious(self.id)),
+                    widget::container(
+                        widget::button("merge next")
+                            .style(widget::button::secondary)
+                            .on_press(Message::MergeWithNext(self.id))
+                    )
+                    .id(DebugId::ButtonMergeNext(self.id)),
+                    widget::container(
+                        widget::button("analyze")
+                            .style(widget::button::secondary)
+                            .on_press_maybe(
+                                (!self.excluded).then_some(Message::AnalyzeSpan(self.id))
+                            )
+                    )
+                    .id(DebugId::ButtonAnalyzeSpan(self.id)),
+                    widget::checkbox(is_selected)
+                        .label("select")
+                        .on_toggle(|_| Message::ToggleSpanSelection(self.id)),
+                ]
+                .spacing(5)
+                .wrap()
             ]
             .spacing(5),
         )
@@ -73,6 +169,80 @@ impl AudioSpan {
     pub fn position(&self) -> Duration {
         Duration::from_secs_f32(self.position)
     }
+    // only edited edges, the inputs are rounded and would move an untouched edge
+    pub fn edited_range_input(&self) -> (Option<&str>, Option<&str>) {
+        fn edited(input: &str, value: Duration) -> Option<&str> {
+            (input.trim() != fmt_secs(value)).then_some(input)
+        }
+        (
+            edited(&self.start_input, self.start),
+            edited(&self.end_input, self.end),
+        )
+    }
+    pub fn set_start_input(&mut self, text: String) {
+        self.start_input = text;
+    }
+    pub fn set_end_input(&mut self, text: String) {
+        self.end_input = text;
+    }
+    // split points are redistributed by the caller, see `Audio::trim_span`
+    pub fn set_range(&mut self, start: Duration, end: Duration) {
+        self.start = start;
+        self.end = end;
+        self.selected_split_points
+            .retain(|p| start < *p && *p < end);
+        self.position = self.position.clamp(start.as_secs_f32(), end.as_secs_f32());
+        self.start_input = fmt_secs(start);
+        self.end_input = fmt_secs(end);
+        self.levels = None;
+    }
+    pub fn levels(&self) -> Option<Levels> {
+        self.levels
+    }
+    pub fn set_levels(&mut self, levels: Levels) {
+        self.levels = Some(levels);
+    }
+    pub fn defects(&self) -> &[Defect] {
+        &self.defects
+    }
+    // see `Audio::set_defects`
+    pub fn set_defects(&mut self, defects: Vec<Defect>) {
+        self.defects = defects;
+    }
+    pub fn regions(&self) -> &[ClassRegion] {
+        &self.regions
+    }
+    pub fn set_regions(&mut self, regions: Vec<ClassRegion>) {
+        self.regions = regions;
+    }
+    // `None` for split points that weren't detected from a silence
+    pub fn score(&self, split_point: Duration) -> Option<f32> {
+        self.scores
+            .iter()
+            .find(|(p, _)| *p == split_point)
+            .map(|(_, s)| *s)
+    }
+    // see `Audio::set_scores`
+    pub fn set_scores(&mut self, scores: Vec<(Duration, f32)>) {
+        self.scores = scores;
+    }
+    // the envelope of the whole file, indexed by absolute time
+    pub fn envelope(&self) -> &Envelope {
+        &self.envelope
+    }
+    pub fn threshold(&self) -> Option<f32> {
+        self.threshold
+    }
+    pub fn set_envelope(&mut self, envelope: Arc<Envelope>, threshold: Option<f32>) {
+        self.envelope = envelope;
+        self.threshold = threshold;
+    }
+    pub fn is_excluded(&self) -> bool {
+        self.excluded
+    }
+    pub fn set_excluded(&mut self, excluded: bool) {
+        self.excluded = excluded;
+    }
     pub fn set_name(&mut self, name: String) {
         self.name = name;
     }
@@ -106,6 +276,13 @@ impl AudioSpan {
         }
         fits
     }
+    pub fn set_selected_split_points(&mut self, split_points: &[Duration]) {
+        self.selected_split_points = split_points
+            .iter()
+            .filter(|p| self.contains(**p))
+            .copied()
+            .collect();
+    }
     pub fn toggle_split_point_selection(&mut self, split_point: Duration) -> bool {
         let fits = self.contains(split_point);
         if fits {
@@ -122,6 +299,19 @@ impl AudioSpan {
         }
         fits
     }
+    // the former boundary becomes a split point again, so the merge can be re-split
+    pub fn absorb(&mut self, next: AudioSpan) {
+        self.split_points.push(self.end);
+        self.end = next.end;
+        self.split_points.extend(next.split_points);
+        self.split_points.sort();
+        self.split_points.dedup();
+        self.selected_split_points
+            .extend(next.selected_split_points);
+        self.start_input = fmt_secs(self.start);
+        self.end_input = fmt_secs(self.end);
+        self.levels = None;
+    }
     pub fn clear_split_points(&mut self) {
         self.split_points.clear();
     }
@@ -129,3 +319,7 @@ impl AudioSpan {
         !self.selected_split_points.is_empty()
     }
 }
+
+fn fmt_secs(duration: Duration) -> String {
+    format!("{:.2}", duration.as_secs_f32())
+}
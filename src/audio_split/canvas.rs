@@ -6,11 +6,32 @@ use iced::{
     widget::canvas::{self, Path},
 };
 
-use crate::audio_split::{AudioSpan, Message};
+use crate::audio_split::{
+    AudioSpan, Message, analyze::DefectKind, classify::SoundClass, selection::Selection,
+};
+
+// width in px at both ends of a span that grabs the trim handle
+const HANDLE_WIDTH: f32 = 6.0;
+// shorter drags count as a click and leave the selection and the span range alone
+const MIN_SELECTION_WIDTH: f32 = 3.0;
+// level drawn at zero height, full scale fills the canvas
+const ENVELOPE_FLOOR_DB: f32 = -80.0;
+const ENVELOPE_BAR_WIDTH: f32 = 2.0;
 
 #[derive(Debug, Default, Clone)]
 pub struct MouseInteraction {
     hovered: Option<usize>,
+    // grabbed trim handle and the x where the drag started
+    dragging: Option<(Edge, f32)>,
+    // x where a range selection started
+    selecting: Option<f32>,
+    drag_x: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Edge {
+    Start,
+    End,
 }
 
 impl canvas::Program<Message> for AudioSpan {
@@ -23,7 +44,78 @@ impl canvas::Program<Message> for AudioSpan {
         cursor: Cursor,
     ) -> Option<canvas::Action<Message>> {
         let mut hovered = false;
+        if self.is_excluded() {
+            return None;
+        }
+        if let Some((edge, start_x)) = state.dragging {
+            return match event {
+                iced::Event::Mouse(iced::mouse::Event::CursorMoved { position }) => {
+                    // unclamped so a handle can reach into an adjacent excluded span;
+                    // `Audio::trim_span` limits how far the span may grow
+                    state.drag_x = position.x - bounds.x;
+                    Some(canvas::Action::request_redraw())
+                }
+                iced::Event::Mouse(iced::mouse::Event::ButtonReleased(_)) => {
+                    state.dragging = None;
+                    if f32::abs(state.drag_x - start_x) < MIN_SELECTION_WIDTH {
+                        return Some(canvas::Action::request_redraw());
+                    }
+                    let time = get_time(state.drag_x / bounds.width, self.start(), self.end());
+                    Some(canvas::Action::publish(match edge {
+                        Edge::Start => Message::TrimSpan(self.id(), time, self.end()),
+                        Edge::End => Message::TrimSpan(self.id(), self.start(), time),
+                    }))
+                }
+                _ => None,
+            };
+        }
+        if let Some(start_x) = state.selecting {
+            return match event {
+                iced::Event::Mouse(iced::mouse::Event::CursorMoved { position }) => {
+                    state.drag_x = (position.x - bounds.x).clamp(0.0, bounds.width);
+                    Some(canvas::Action::request_redraw())
+                }
+                iced::Event::Mouse(iced::mouse::Event::ButtonReleased(_)) => {
+                    state.selecting = None;
+                    if f32::abs(state.drag_x - start_x) < MIN_SELECTION_WIDTH {
+                        return Some(canvas::Action::request_redraw());
+                    }
+                    let from = get_time(start_x / bounds.width, self.start(), self.end());
+                    let to = get_time(state.drag_x / bounds.width, self.start(), self.end());
+                    Some(canvas::Action::publish(Message::BulkSelect(
+                        Selection::Range(from, to),
+                    )))
+                }
+                _ => None,
+            };
+        }
         if let Some(cursor_position) = cursor.position_in(bounds) {
+            if let iced::Event::Mouse(iced::mouse::Event::ButtonPressed(
+                iced::mouse::Button::Left,
+            )) = event
+            {
+                let edge = if cursor_position.x <= HANDLE_WIDTH {
+                    Some(Edge::Start)
+                } else if cursor_position.x >= bounds.width - HANDLE_WIDTH {
+                    Some(Edge::End)
+                } else {
+                    None
+                };
+                if let Some(edge) = edge {
+                    state.dragging = Some((edge, cursor_position.x));
+                    state.drag_x = cursor_position.x;
+                    return Some(canvas::Action::capture());
+                }
+                let on_split_point = self.split_points().iter().any(|s| {
+                    let x = get_x_percentage(*s, self.start(), self.end()) * bounds.width;
+                    f32::abs(cursor_position.x - x) <= 5.5
+                });
+                if !on_split_point {
+                    state.selecting = Some(cursor_position.x);
+                    state.drag_x = cursor_position.x;
+                    return Some(canvas::Action::capture());
+                }
+            }
             if f32::abs(cursor_position.y - (bounds.height / 2.)) > 15. {
                 state.hovered = None;
                 return None;
@@ -31,8 +123,11 @@ impl canvas::Program<Message> for AudioSpan {
             for (index, splice) in self.split_points().iter().enumerate() {
                 let x_percentage = get_x_percentage(*splice, self.start(), self.end());
                 if f32::abs(cursor_position.x - (x_percentage * bounds.width)) <= 5.5 {
-                    if let iced::Event::Mouse(iced::mouse::Event::ButtonPressed(_)) = event {
-                        return Some(canvas::Action::publish(Message::ClickSplitPoint(*splice)));
+                    if let iced::Event::Mouse(iced::mouse::Event::ButtonPressed(button)) = event {
+                        return Some(canvas::Action::publish(match button {
+                            iced::mouse::Button::Right => Message::PreviewSplitPoint(*splice),
+                            _ => Message::ClickSplitPoint(*splice),
+                        }));
                     } else {
                         state.hovered = Some(index);
                         hovered = true;
@@ -57,11 +152,42 @@ impl canvas::Program<Message> for AudioSpan {
     ) -> Vec<canvas::Geometry<Renderer>> {
         let mut frame = canvas::Frame::new(renderer, bounds.size());
         let y_center = bounds.height / 2.0;
+        let level_height = |db: f32| {
+            ((db - ENVELOPE_FLOOR_DB) / -ENVELOPE_FLOOR_DB).clamp(0.0, 1.0) * bounds.height
+        };
+
+        if !self.is_excluded() && !self.envelope().is_empty() {
+            let color = theme.extended_palette().background.strong.color;
+            let mut x = 0.0;
+            while x < bounds.width {
+                let time = get_time(x / bounds.width, self.start(), self.end());
+                if let Some(level) = self.envelope().level_at(time) {
+                    let height = level_height(level);
+                    let bar = Path::rectangle(
+                        Point::new(x, y_center - height / 2.0),
+                        Size::new(ENVELOPE_BAR_WIDTH, height),
+                    );
+                    frame.fill(&bar, color);
+                }
+                x += ENVELOPE_BAR_WIDTH;
+            }
+            if let Some(threshold) = self.threshold() {
+                let height = level_height(threshold);
+                for y in [y_center - height / 2.0, y_center + height / 2.0] {
+                    let line = Path::rectangle(Point::new(0.0, y), Size::new(bounds.width, 1.0));
+                    frame.fill(&line, theme.extended_palette().warning.base.color);
+                }
+            }
+        }
 
         let base_line = Path::rectangle(
             Point::new(0.0, y_center - 5.0),
             Size::new(bounds.width, 10.0),
         );
+        if self.is_excluded() {
+            frame.fill(&base_line, theme.extended_palette().background.strong.color);
+            return vec![frame.into_geometry()];
+        }
         frame.fill(&base_line, theme.extended_palette().secondary.weak.color);
 
         let end_pos = self.end() - self.start();
@@ -82,14 +208,28 @@ impl canvas::Program<Message> for AudioSpan {
 
         for (index, splice) in self.split_points().iter().enumerate() {
             let x_pos_percentage = get_x_percentage(*splice, self.start(), self.end());
+            // weak split points are drawn shorter and paler
+            let score = self.score(*splice).unwrap_or(1.0);
+            let height = 10.0 + 10.0 * score;
             let splice_line = Path::rectangle(
-                Point::new(bounds.width * x_pos_percentage - 5.0, y_center - 10.0),
-                Size::new(10.0, 20.0),
+                Point::new(
+                    bounds.width * x_pos_percentage - 5.0,
+                    y_center - height / 2.0,
+                ),
+                Size::new(10.0, height),
             );
             if self.selected_split_points().contains(splice) {
                 frame.fill(&splice_line, theme.extended_palette().danger.base.color);
             } else {
-                frame.fill(&splice_line, theme.extended_palette().primary.base.color);
+                frame.fill(
+                    &splice_line,
+                    theme
+                        .extended_palette()
+                        .primary
+                        .base
+                        .color
+                        .scale_alpha(0.4 + 0.6 * score),
+                );
                 if let Some(i) = state.hovered
                     && i == index
                 {
@@ -98,6 +238,57 @@ impl canvas::Program<Message> for AudioSpan {
             }
         }
 
+        for defect in self.defects() {
+            let start = get_x_percentage(defect.start(), self.start(), self.end()) * bounds.width;
+            let end = get_x_percentage(defect.end(), self.start(), self.end()) * bounds.width;
+            let marker = Path::rectangle(
+                Point::new(start.min(bounds.width - 2.0), 0.0),
+                Size::new((end.min(bounds.width) - start).max(2.0), 6.0),
+            );
+            let color = match defect.kind() {
+                DefectKind::Clipping => theme.extended_palette().danger.base.color,
+                DefectKind::Dropout => theme.extended_palette().warning.base.color,
+            };
+            frame.fill(&marker, color);
+        }
+
+        for region in self.regions() {
+            let start = get_x_percentage(region.start(), self.start(), self.end()) * bounds.width;
+            let end = get_x_percentage(region.end(), self.start(), self.end()) * bounds.width;
+            let band = Path::rectangle(
+                Point::new(start, bounds.height - 6.0),
+                Size::new(end.min(bounds.width) - start, 6.0),
+            );
+            let color = match region.class() {
+                SoundClass::Speech => theme.extended_palette().success.base.color,
+                SoundClass::Music => theme.extended_palette().primary.base.color,
+                SoundClass::Noise => theme.extended_palette().background.strong.color,
+            };
+            frame.fill(&band, color);
+        }
+
+        let handle_color = theme.extended_palette().primary.strong.color;
+        for x in [0.0, bounds.width - 3.0] {
+            let handle = Path::rectangle(Point::new(x, y_center - 10.0), Size::new(3.0, 20.0));
+            frame.fill(&handle, handle_color);
+        }
+        if state.dragging.is_some() {
+            let x = state.drag_x.clamp(0.0, bounds.width - 2.0);
+            let drag_line = Path::rectangle(Point::new(x, 0.0), Size::new(2.0, bounds.height));
+            frame.fill(&drag_line, theme.extended_palette().success.base.color);
+        }
+
+        if let Some(start_x) = state.selecting {
+            let band = Path::rectangle(
+                Point::new(start_x.min(state.drag_x), 0.0),
+                Size::new(f32::abs(state.drag_x - start_x), bounds.height),
+            );
+            frame.fill(
+                &band,
+                theme.extended_palette().primary.weak.color.scale_alpha(0.4),
+            );
+        }
+
         // Then, we produce the geometry
         vec![frame.into_geometry()]
     }
@@ -109,3 +300,8 @@ fn get_x_percentage(value: Duration, start: Duration, end: Duration) -> f32 {
     }
     value.saturating_sub(start).as_secs_f32() / end.saturating_sub(start).as_secs_f32()
 }
+
+fn get_time(x_percentage: f32, start: Duration, end: Duration) -> Duration {
+    let secs = start.as_secs_f32() + x_percentage * end.saturating_sub(start).as_secs_f32();
+    Duration::from_secs_f32(secs.max(0.0))
+}
@@ -0,0 +1,254 @@
+use std::{f32::consts::PI, fmt, fs::File, path::PathBuf, time::Duration};
+
+use rodio::Source;
+
+use crate::audio_split::error::Error;
+
+// every window of the file gets one class
+pub const CLASS_WINDOW: Duration = Duration::from_secs(1);
+// samples per FFT frame, a power of two
+pub const FRAME_SIZE: usize = 1024;
+const SILENCE_DB: f32 = -100.0;
+// quieter windows are noise, whatever their spectrum
+const NOISE_LEVEL_DB: f32 = -50.0;
+// white noise has a flatness of about 0.56, tones are close to 0
+const NOISE_FLATNESS: f32 = 0.4;
+// syllables and pauses make the frame energy of speech jump, music is steadier
+const SPEECH_ENERGY_DEVIATION_DB: f32 = 6.0;
+// voiced and unvoiced sounds alternate in speech
+const SPEECH_ZCR_DEVIATION: f32 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundClass {
+    Speech,
+    Music,
+    Noise,
+}
+
+impl fmt::Display for SoundClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SoundClass::Speech => write!(f, "speech"),
+            SoundClass::Music => write!(f, "music"),
+            SoundClass::Noise => write!(f, "noise"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClassRegion {
+    class: SoundClass,
+    start: Duration,
+    end: Duration,
+}
+
+impl ClassRegion {
+    pub fn new(class: SoundClass, start: Duration, end: Duration) -> Self {
+        Self { class, start, end }
+    }
+    pub fn class(&self) -> SoundClass {
+        self.class
+    }
+    pub fn start(&self) -> Duration {
+        self.start
+    }
+    pub fn end(&self) -> Duration {
+        self.end
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Features {
+    // dBFS
+    pub level: f32,
+    // mean spectral flatness of the frames, 0 for a pure tone
+    pub flatness: f32,
+    // standard deviation of the frame energy in dB
+    pub energy_deviation: f32,
+    // standard deviation of the zero-crossing rate per sample
+    pub zcr_deviation: f32,
+}
+
+impl Features {
+    // `samples` are mono, windows shorter than a frame are silent
+    pub fn new(samples: &[f32]) -> Self {
+        let frames: Vec<&[f32]> = samples.chunks_exact(FRAME_SIZE).collect();
+        if frames.is_empty() {
+            return Self {
+                level: SILENCE_DB,
+                ..Self::default()
+            };
+        }
+        let mut energies = Vec::with_capacity(frames.len());
+        let mut zcrs = Vec::with_capacity(frames.len());
+        // pauses have a flat spectrum too, only audible frames count
+        let mut flatness = Vec::with_capacity(frames.len());
+        let mut sum_of_squares = 0.0;
+        for frame in &frames {
+            let squares: f32 = frame.iter().map(|s| s * s).sum();
+            sum_of_squares += squares;
+            let energy = to_db(squares / FRAME_SIZE as f32);
+            energies.push(energy);
+            let crossings = frame
+                .windows(2)
+                .filter(|w| (w[0] < 0.0) != (w[1] < 0.0))
+                .count();
+            zcrs.push(crossings as f32 / FRAME_SIZE as f32);
+            if energy >= NOISE_LEVEL_DB {
+                flatness.push(spectral_flatness(&power_spectrum(frame)));
+            }
+        }
+        Self {
+            level: to_db(sum_of_squares / (frames.len() * FRAME_SIZE) as f32),
+            flatness: flatness.iter().sum::<f32>() / flatness.len().max(1) as f32,
+            energy_deviation: deviation(&energies),
+            zcr_deviation: deviation(&zcrs),
+        }
+    }
+    pub fn class(&self) -> SoundClass {
+        if self.level < NOISE_LEVEL_DB || self.flatness > NOISE_FLATNESS {
+            SoundClass::Noise
+        } else if self.energy_deviation > SPEECH_ENERGY_DEVIATION_DB
+            || self.zcr_deviation > SPEECH_ZCR_DEVIATION
+        {
+            SoundClass::Speech
+        } else {
+            SoundClass::Music
+        }
+    }
+}
+
+fn to_db(power: f32) -> f32 {
+    (10.0 * power.log10()).max(SILENCE_DB)
+}
+
+fn deviation(values: &[f32]) -> f32 {
+    let mean = values.iter().sum::<f32>() / values.len() as f32;
+    (values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / values.len() as f32).sqrt()
+}
+
+// geometric over arithmetic mean of the power spectrum
+fn spectral_flatness(spectrum: &[f32]) -> f32 {
+    let power: Vec<f32> = spectrum.iter().map(|p| p + 1e-12).collect();
+    let log_mean = power.iter().map(|p| p.ln()).sum::<f32>() / power.len() as f32;
+    let mean = power.iter().sum::<f32>() / power.len() as f32;
+    log_mean.exp() / mean
+}
+
+// power of the bins between DC and Nyquist of a Hann windowed frame
+pub fn power_spectrum(frame: &[f32]) -> Vec<f32> {
+    let n = frame.len();
+    let mut re: Vec<f32> = frame
+        .iter()
+        .enumerate()
+        .map(|(i, s)| s * (0.5 - 0.5 * (2.0 * PI * i as f32 / n as f32).cos()))
+        .collect();
+    let mut im = vec![0.0; n];
+    fft(&mut re, &mut im);
+    (1..n / 2).map(|k| re[k] * re[k] + im[k] * im[k]).collect()
+}
+
+// in place radix-2 FFT, the length must be a power of two
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let re_b = re[b] * cos - im[b] * sin;
+                let im_b = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - re_b;
+                im[b] = im[a] - im_b;
+                re[a] += re_b;
+                im[a] += im_b;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+// downmixed samples of the whole file in `window` chunks with their sample rate, the last
+// one may be shorter
+pub fn for_each_window(
+    path: PathBuf,
+    window: Duration,
+    mut f: impl FnMut(&[f32], u32),
+) -> Result<(), Error> {
+    let decoder = rodio::Decoder::try_from(File::open(path)?)?;
+    let channels = decoder.channels().get() as usize;
+    let rate = decoder.sample_rate().get();
+    let window = (window.as_secs_f64() * rate as f64) as usize;
+    let mut samples = Vec::with_capacity(window);
+    let (mut frame_sum, mut channel) = (0.0, 0);
+    for sample in decoder {
+        frame_sum += sample;
+        channel += 1;
+        if channel == channels {
+            samples.push(frame_sum / channels as f32);
+            (frame_sum, channel) = (0.0, 0);
+        }
+        if samples.len() == window {
+            f(&samples, rate);
+            samples.clear();
+        }
+    }
+    if !samples.is_empty() {
+        f(&samples, rate);
+    }
+    Ok(())
+}
+
+pub async fn classify_audio(path: PathBuf) -> Result<Vec<ClassRegion>, Error> {
+    tokio::task::spawn_blocking(move || {
+        let mut classes = Vec::new();
+        for_each_window(path, CLASS_WINDOW, |samples, _| {
+            classes.push(Features::new(samples).class())
+        })?;
+        Ok(regions(&smooth(&classes), CLASS_WINDOW))
+    })
+    .await
+    .unwrap()
+}
+
+// a single window that differs from both of its agreeing neighbours takes their class
+pub fn smooth(classes: &[SoundClass]) -> Vec<SoundClass> {
+    let mut smoothed = classes.to_vec();
+    for i in 1..classes.len().saturating_sub(1) {
+        if classes[i - 1] == classes[i + 1] {
+            smoothed[i] = classes[i - 1];
+        }
+    }
+    smoothed
+}
+
+pub fn regions(classes: &[SoundClass], window: Duration) -> Vec<ClassRegion> {
+    let mut regions: Vec<ClassRegion> = Vec::new();
+    for (i, class) in classes.iter().enumerate() {
+        let end = window * (i as u32 + 1);
+        match regions.last_mut() {
+            Some(last) if last.class == *class => last.end = end,
+            _ => regions.push(ClassRegion::new(*class, window * i as u32, end)),
+        }
+    }
+    regions
+}
+
+pub fn class_changes(regions: &[ClassRegion]) -> Vec<Duration> {
+    regions.iter().skip(1).map(ClassRegion::start).collect()
+}
@@ -6,23 +6,105 @@ pub enum DebugId {
     InfoAudioLoaded,
     InfoSplitPointsDetected(usize),
     InfoSplits(usize),
+    InfoPreviewCut,
+    InfoSpansMerged(usize),
+    InfoLoopStartSet,
+    InfoLoopRangeSet,
+    InfoLoudnessNormalized,
+    InfoLevelsMeasured,
+    InfoTargetLengthSelected(usize),
+    InfoSplitPointsConstrained(usize),
+    InfoSplitPointsGenerated(usize),
+    InfoThresholdEstimated,
+    InfoSelectedAboveScore(usize),
+    InfoSelection(usize),
+    InfoRangeAnalyzed(usize),
+    InfoAnalysisCancelled,
+    InfoClassified(usize),
+    InfoTrackBoundaries(usize),
+    InfoMarkersDetected(usize),
     WarningNoAudioLoaded,
     WarningNoSplitPointFound,
     WarningNoSplitPointSelected,
+    WarningInvalidLoopRange,
+    WarningNoSplitPointAtPlayhead,
+    WarningNoAdjacentSpans,
+    WarningInvalidTrimRange,
+    WarningInvalidSegmentLength,
+    WarningNoNoiseFloor,
+    WarningInvalidAnalysisRange,
+    WarningNotClassified,
+    WarningNoMarkersFound,
     ErrorAudioDecoder,
     ErrorIO,
     ErrorParseFloat,
+    ErrorParseInt,
     ErrorNegativeDuration,
+    ErrorPercentOutOfRange,
+    ErrorKeyBindings,
+    ErrorLoudness,
+    ErrorMarkerTone,
     ButtonOpen,
     ButtonPlay,
     ButtonPause,
     ButtonAnalyze,
+    ButtonAnalyzeLevels,
+    ButtonAutoThreshold,
+    ButtonSelectAboveScore,
+    ButtonSelectAll,
+    ButtonSelectNone,
+    ButtonInvertSelection,
+    ButtonSelectEveryNth,
+    ButtonDefect(usize),
     ButtonSplit,
     ButtonExport,
     ButtonDelete(u32),
+    ButtonRestore(u32),
     ButtonUndo,
+    ButtonRedo,
+    ButtonMergeNext(u32),
+    ButtonAnalyzeSpan(u32),
+    ButtonAnalyzeRange,
+    ButtonCancelAnalysis,
+    ButtonClassify,
+    ButtonSplitAtClassChanges,
+    ButtonMergePrevious(u32),
+    ButtonMergeSelected,
+    ButtonHelp,
+    ButtonCloseHelp,
+    ButtonLoop(u32),
+    ButtonLoopStart,
+    ButtonLoopEnd,
+    ButtonLoopClear,
     TextInputThreshold,
+    PickListPreset,
     TextInputDuration,
+    TextInputMinSegmentLength,
+    TextInputMaxSegments,
+    TextInputPreview,
+    TextInputTrimPadding,
+    PickListSplitMode,
+    TextInputInterval,
+    TextInputParts,
+    TextInputSnapWindow,
+    TextInputMinScore,
+    TextInputNth,
+    TextInputAnalyzeFrom,
+    TextInputAnalyzeTo,
+    TextInputMarkerTone,
+    TextInputTargetLength,
+    TextInputMinLength,
+    TextInputMaxLength,
+    TextInputFadeIn,
+    TextInputFadeOut,
+    PickListFadeCurve,
+    TextInputLoudnessTarget,
+    TextInputTruePeak,
+    PickListNormalizeMode,
+    TextInputSpanName(u32),
+    TextInputSpanStart(u32),
+    TextInputSpanEnd(u32),
+    HelpOverlay,
     Canvas,
 }
 
@@ -30,6 +112,31 @@ impl From<DebugId> for Id {
     fn from(value: DebugId) -> Self {
         match value {
             DebugId::ButtonDelete(v) => Id::from(format!("ButtonDelete:{v}")),
+            DebugId::ButtonRestore(v) => Id::from(format!("ButtonRestore:{v}")),
+            DebugId::ButtonLoop(v) => Id::from(format!("ButtonLoop:{v}")),
+            DebugId::ButtonDefect(v) => Id::from(format!("ButtonDefect:{v}")),
+            DebugId::InfoSelection(v) => Id::from(format!("InfoSelection:{v}")),
+            DebugId::InfoSelectedAboveScore(v) => Id::from(format!("InfoSelectedAboveScore:{v}")),
+            DebugId::InfoSplitPointsGenerated(v) => {
+                Id::from(format!("InfoSplitPointsGenerated:{v}"))
+            }
+            DebugId::InfoSplitPointsConstrained(v) => {
+                Id::from(format!("InfoSplitPointsConstrained:{v}"))
+            }
+            DebugId::InfoTargetLengthSelected(v) => {
+                Id::from(format!("InfoTargetLengthSelected:{v}"))
+            }
+            DebugId::ButtonMergeNext(v) => Id::from(format!("ButtonMergeNext:{v}")),
+            DebugId::ButtonAnalyzeSpan(v) => Id::from(format!("ButtonAnalyzeSpan:{v}")),
+            DebugId::InfoRangeAnalyzed(v) => Id::from(format!("InfoRangeAnalyzed:{v}")),
+            DebugId::InfoClassified(v) => Id::from(format!("InfoClassified:{v}")),
+            DebugId::InfoTrackBoundaries(v) => Id::from(format!("InfoTrackBoundaries:{v}")),
+            DebugId::InfoMarkersDetected(v) => Id::from(format!("InfoMarkersDetected:{v}")),
+            DebugId::ButtonMergePrevious(v) => Id::from(format!("ButtonMergePrevious:{v}")),
+            DebugId::InfoSpansMerged(v) => Id::from(format!("InfoSpansMerged:{v}")),
+            DebugId::TextInputSpanName(v) => Id::from(format!("TextInputSpanName:{v}")),
+            DebugId::TextInputSpanStart(v) => Id::from(format!("TextInputSpanStart:{v}")),
+            DebugId::TextInputSpanEnd(v) => Id::from(format!("TextInputSpanEnd:{v}")),
             DebugId::InfoSplitPointsDetected(v) => Id::from(format!("InfoSplitPointsSelected:{v}")),
             DebugId::InfoSplits(v) => Id::from(format!("InfoSplits:{v}")),
 
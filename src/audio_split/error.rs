@@ -1,6 +1,10 @@
-use std::{fmt, num::ParseFloatError, sync::Arc};
+use std::{
+    fmt,
+    num::{ParseFloatError, ParseIntError},
+    sync::Arc,
+};
 
-use super::debug_id::DebugId;
+use super::{debug_id::DebugId, shortcuts::BindingError};
 #[derive(Debug, Clone)]
 pub struct Error {
     kind: ErrorKind,
@@ -21,7 +25,12 @@ pub enum ErrorKind {
     AudioDecoder(rodio::decoder::DecoderError),
     IO(Arc<std::io::Error>),
     Parsing(ParseFloatError),
+    ParsingInt(ParseIntError),
     NegativeDuration,
+    PercentOutOfRange,
+    KeyBindings(Vec<BindingError>),
+    Loudness(String),
+    MarkerTone(String),
 }
 impl From<rodio::decoder::DecoderError> for Error {
     fn from(value: rodio::decoder::DecoderError) -> Self {
@@ -35,6 +44,12 @@ impl From<std::io::Error> for Error {
     }
 }
 
+impl From<ParseIntError> for Error {
+    fn from(value: ParseIntError) -> Self {
+        Error::new(ErrorKind::ParsingInt(value), DebugId::ErrorParseInt)
+    }
+}
+
 impl From<ParseFloatError> for Error {
     fn from(value: ParseFloatError) -> Self {
         Error::new(ErrorKind::Parsing(value), DebugId::ErrorParseFloat)
@@ -50,7 +65,21 @@ impl fmt::Display for Error {
                 f,
                 "failed to parse float; please check duration and threshold"
             ),
+            ErrorKind::ParsingInt(_) => write!(f, "failed to parse a whole number"),
             ErrorKind::NegativeDuration => write!(f, "a negative duration value is not allowed"),
+            ErrorKind::PercentOutOfRange => write!(f, "a percentage must be between 0 and 100"),
+            ErrorKind::KeyBindings(errors) => {
+                write!(f, "invalid key bindings file, using the defaults:")?;
+                for error in errors {
+                    write!(f, " {error};")?;
+                }
+                Ok(())
+            }
+            ErrorKind::Loudness(file) => write!(f, "failed to measure the loudness of {file}"),
+            ErrorKind::MarkerTone(input) => write!(
+                f,
+                "invalid marker tone {input}; expected a frequency like 1000 Hz or DTMF digits"
+            ),
         }
     }
 }
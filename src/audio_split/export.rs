@@ -0,0 +1,116 @@
+use std::{fmt, time::Duration};
+
+use crate::audio_split::{analyze::Silence, audio_span::AudioSpan, loudness::Normalize};
+
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    // silence kept at the edges of a segment, `None` keeps the whole silence
+    pub trim_silence: Option<Duration>,
+    pub fade_in: Duration,
+    pub fade_out: Duration,
+    pub fade_curve: FadeCurve,
+    pub snap_to_zero_crossing: bool,
+    pub normalize: Option<Normalize>,
+}
+
+impl ExportOptions {
+    pub fn segment_range(&self, span: &AudioSpan, silences: &[Silence]) -> (Duration, Duration) {
+        let (mut start, mut end) = (span.start(), span.end());
+        if let Some(padding) = self.trim_silence {
+            if let Some(leading) = silences
+                .iter()
+                .find(|s| s.start() <= start && start < s.end())
+            {
+                start = start.max(leading.end().saturating_sub(padding));
+            }
+            if let Some(trailing) = silences.iter().find(|s| s.start() < end && end <= s.end()) {
+                end = end.min(trailing.start() + padding);
+            }
+        }
+        // a segment that is silent as a whole is exported untouched
+        if start < end {
+            (start, end)
+        } else {
+            (span.start(), span.end())
+        }
+    }
+    // `-ss`/`-to` are output options, so the filters see the timestamps of the source;
+    // each fade is limited to half of the segment
+    pub fn filter(&self, start: Duration, end: Duration, gain_db: Option<f32>) -> Option<String> {
+        let half = end.saturating_sub(start) / 2;
+        let (fade_in, fade_out) = (self.fade_in.min(half), self.fade_out.min(half));
+        let curve = self.fade_curve.ffmpeg_name();
+        let mut filters = Vec::new();
+        if let Some(gain) = gain_db {
+            filters.push(format!("volume={gain:.2}dB"));
+        }
+        if !fade_in.is_zero() {
+            filters.push(format!(
+                "afade=t=in:st={:.6}:d={:.6}:curve={curve}",
+                start.as_secs_f64(),
+                fade_in.as_secs_f64()
+            ));
+        }
+        if !fade_out.is_zero() {
+            filters.push(format!(
+                "afade=t=out:st={:.6}:d={:.6}:curve={curve}",
+                (end - fade_out).as_secs_f64(),
+                fade_out.as_secs_f64()
+            ));
+        }
+        (!filters.is_empty()).then(|| filters.join(","))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FadeCurve {
+    #[default]
+    Linear,
+    QuarterSine,
+    HalfSine,
+    Logarithmic,
+    Exponential,
+}
+
+impl FadeCurve {
+    pub const ALL: [FadeCurve; 5] = [
+        FadeCurve::Linear,
+        FadeCurve::QuarterSine,
+        FadeCurve::HalfSine,
+        FadeCurve::Logarithmic,
+        FadeCurve::Exponential,
+    ];
+    // curve names of ffmpeg's `afade` filter, also used in the settings file
+    pub fn ffmpeg_name(self) -> &'static str {
+        match self {
+            FadeCurve::Linear => "tri",
+            FadeCurve::QuarterSine => "qsin",
+            FadeCurve::HalfSine => "hsin",
+            FadeCurve::Logarithmic => "log",
+            FadeCurve::Exponential => "exp",
+        }
+    }
+    pub fn from_ffmpeg_name(name: &str) -> Option<Self> {
+        FadeCurve::ALL.into_iter().find(|c| c.ffmpeg_name() == name)
+    }
+}
+
+impl fmt::Display for FadeCurve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FadeCurve::Linear => "linear",
+            FadeCurve::QuarterSine => "quarter sine",
+            FadeCurve::HalfSine => "half sine",
+            FadeCurve::Logarithmic => "logarithmic",
+            FadeCurve::Exponential => "exponential",
+        };
+        write!(f, "{name}")
+    }
+}
+
+// index of the sample right after the sign change closest to `center`
+pub fn nearest_zero_crossing(samples: &[f32], center: usize) -> Option<usize> {
+    (1..samples.len())
+        .filter(|&i| (samples[i - 1] < 0.0) != (samples[i] < 0.0))
+        .min_by_key(|i| i.abs_diff(center))
+}
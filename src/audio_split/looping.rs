@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use crate::audio_split::audio::Audio;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Looping {
+    Span(u32),
+    Range(Duration, Duration),
+}
+
+impl Looping {
+    // `None` when the looped span no longer exists, f.e. after an undo
+    pub fn range(&self, audio: &Audio) -> Option<(Duration, Duration)> {
+        match *self {
+            Looping::Span(id) => audio
+                .spans()
+                .iter()
+                .find(|s| s.id() == id)
+                .map(|s| (s.start(), s.end())),
+            Looping::Range(start, end) => Some((start, end)),
+        }
+    }
+    pub fn span(&self) -> Option<u32> {
+        match *self {
+            Looping::Span(id) => Some(id),
+            Looping::Range(..) => None,
+        }
+    }
+}
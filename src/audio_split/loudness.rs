@@ -0,0 +1,352 @@
+use std::{
+    fmt,
+    fs::File,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use rodio::Source;
+use tokio::process::Command;
+
+use crate::audio_split::{
+    audio_span::AudioSpan,
+    debug_id::DebugId,
+    error::{Error, ErrorKind},
+};
+
+// loudness range the `loudnorm` filter assumes when only measuring
+const LOUDNORM_RANGE: f32 = 11.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Loudness {
+    // LUFS, `-inf` for digital silence
+    integrated: f32,
+    // dBTP
+    true_peak: f32,
+}
+
+impl Loudness {
+    pub fn new(integrated: f32, true_peak: f32) -> Self {
+        Self {
+            integrated,
+            true_peak,
+        }
+    }
+    pub fn integrated(&self) -> f32 {
+        self.integrated
+    }
+    pub fn true_peak(&self) -> f32 {
+        self.true_peak
+    }
+    // energy weighted by duration, gating of the single measurements is ignored
+    pub fn combined(measurements: &[(Loudness, Duration)]) -> Self {
+        let total: f64 = measurements.iter().map(|(_, d)| d.as_secs_f64()).sum();
+        let energy: f64 = measurements
+            .iter()
+            .map(|(l, d)| 10f64.powf(l.integrated as f64 / 10.0) * d.as_secs_f64())
+            .sum();
+        let true_peak = measurements
+            .iter()
+            .map(|(l, _)| l.true_peak)
+            .fold(f32::NEG_INFINITY, f32::max);
+        Self::new((10.0 * (energy / total).log10()) as f32, true_peak)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NormalizeMode {
+    #[default]
+    PerSegment,
+    // one gain for all exported segments, keeps their relative levels
+    WholeSet,
+}
+
+impl NormalizeMode {
+    pub const ALL: [NormalizeMode; 2] = [NormalizeMode::PerSegment, NormalizeMode::WholeSet];
+    pub fn config_name(self) -> &'static str {
+        match self {
+            NormalizeMode::PerSegment => "segment",
+            NormalizeMode::WholeSet => "set",
+        }
+    }
+    pub fn from_config_name(name: &str) -> Option<Self> {
+        NormalizeMode::ALL
+            .into_iter()
+            .find(|m| m.config_name() == name)
+    }
+}
+
+impl fmt::Display for NormalizeMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NormalizeMode::PerSegment => write!(f, "per segment"),
+            NormalizeMode::WholeSet => write!(f, "whole set"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Normalize {
+    // LUFS
+    pub target: f32,
+    // dBTP
+    pub true_peak: f32,
+    pub mode: NormalizeMode,
+}
+
+impl Normalize {
+    // linear gain in dB, limited so the true peak stays below the ceiling
+    pub fn gain(&self, loudness: Loudness) -> f32 {
+        if !loudness.integrated.is_finite() {
+            return 0.0;
+        }
+        let gain = self.target - loudness.integrated;
+        if loudness.true_peak.is_finite() {
+            gain.min(self.true_peak - loudness.true_peak)
+        } else {
+            gain
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentGain {
+    pub file: String,
+    pub loudness: Loudness,
+    pub gain: f32,
+}
+
+pub fn report(gains: &[SegmentGain]) -> String {
+    let mut report = "file,integrated_lufs,true_peak_dbtp,gain_db\n".to_string();
+    for g in gains {
+        report.push_str(&format!(
+            "{},{:.1},{:.1},{:.2}\n",
+            g.file, g.loudness.integrated, g.loudness.true_peak, g.gain
+        ));
+    }
+    report
+}
+
+// first pass of `loudnorm`, the gain is applied by a `volume` filter on export
+pub async fn measure_loudness(
+    source: &Path,
+    start: Duration,
+    end: Duration,
+) -> Result<Loudness, Error> {
+    let output = Command::new("ffmpeg")
+        .arg("-hide_banner")
+        .arg("-nostats")
+        .arg("-ss")
+        .arg(format!("{:.6}", start.as_secs_f64()))
+        .arg("-to")
+        .arg(format!("{:.6}", end.as_secs_f64()))
+        .arg("-i")
+        .arg(source)
+        .arg("-af")
+        .arg(format!("loudnorm=LRA={LOUDNORM_RANGE}:print_format=json"))
+        .arg("-f")
+        .arg("null")
+        .arg("-")
+        .output()
+        .await?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    match parse_loudnorm(&stderr) {
+        Some(loudness) if output.status.success() => Ok(loudness),
+        _ => Err(Error::new(
+            ErrorKind::Loudness(source.display().to_string()),
+            DebugId::ErrorLoudness,
+        )),
+    }
+}
+
+// reads `"input_i" : "-23.05"` style fields of the json block `loudnorm` prints
+pub fn parse_loudnorm(stderr: &str) -> Option<Loudness> {
+    let field = |name: &str| -> Option<f32> {
+        let key = format!("\"{name}\"");
+        let rest = &stderr[stderr.rfind(&key)? + key.len()..];
+        let value = rest.split('"').nth(1)?;
+        value.trim().parse().ok()
+    };
+    Some(Loudness::new(field("input_i")?, field("input_tp")?))
+}
+
+// sample magnitude that counts as clipped, integer formats decode to just below 1.0
+pub const CLIP_LEVEL: f32 = 0.999;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Levels {
+    // LUFS
+    pub integrated: f32,
+    // LU
+    pub range: f32,
+    // dBFS
+    pub peak: f32,
+    // dBFS
+    pub rms: f32,
+    pub clipped: usize,
+}
+
+impl Levels {
+    pub fn is_clipping(&self) -> bool {
+        self.clipped > 0
+    }
+}
+
+impl fmt::Display for Levels {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.1} LUFS, LRA {:.1} LU, peak {:.1} dB, RMS {:.1} dB",
+            self.integrated, self.range, self.peak, self.rms
+        )?;
+        if self.is_clipping() {
+            write!(f, ", {} clipped", self.clipped)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct SampleStats {
+    peak: f32,
+    sum_of_squares: f64,
+    count: u64,
+    clipped: usize,
+}
+
+impl SampleStats {
+    fn add(&mut self, sample: f32) {
+        let magnitude = sample.abs();
+        self.peak = self.peak.max(magnitude);
+        self.sum_of_squares += (sample as f64).powi(2);
+        self.count += 1;
+        if magnitude >= CLIP_LEVEL {
+            self.clipped += 1;
+        }
+    }
+    fn peak_db(&self) -> f32 {
+        20.0 * self.peak.log10()
+    }
+    fn rms_db(&self) -> f32 {
+        (10.0 * (self.sum_of_squares / self.count.max(1) as f64).log10()) as f32
+    }
+}
+
+// `spans` are `(id, start, end)` in playback order; integrated loudness and LRA come
+// from ffmpeg's `ebur128`, peak, RMS and clipping from the decoded samples
+pub async fn measure_levels(
+    source: PathBuf,
+    spans: Vec<(u32, Duration, Duration)>,
+) -> Result<Vec<(u32, Levels)>, Error> {
+    let stats = {
+        let (source, spans) = (source.clone(), spans.clone());
+        tokio::task::spawn_blocking(move || sample_stats(&source, &spans))
+            .await
+            .unwrap()?
+    };
+    let mut levels = Vec::new();
+    for ((id, start, end), stats) in spans.into_iter().zip(stats) {
+        let (integrated, range) = measure_r128(&source, start, end).await?;
+        levels.push((
+            id,
+            Levels {
+                integrated,
+                range,
+                peak: stats.peak_db(),
+                rms: stats.rms_db(),
+                clipped: stats.clipped,
+            },
+        ));
+    }
+    Ok(levels)
+}
+
+fn sample_stats(
+    source: &Path,
+    spans: &[(u32, Duration, Duration)],
+) -> Result<Vec<SampleStats>, Error> {
+    let decoder = rodio::Decoder::try_from(File::open(source)?)?;
+    let channels = decoder.channels().get() as u64;
+    let rate = decoder.sample_rate().get() as f64;
+    let to_frame = |d: Duration| (d.as_secs_f64() * rate) as u64;
+    let frames: Vec<(u64, u64)> = spans
+        .iter()
+        .map(|(_, start, end)| (to_frame(*start), to_frame(*end)))
+        .collect();
+    let mut stats = vec![SampleStats::default(); spans.len()];
+    let mut current = 0;
+    for (index, sample) in decoder.enumerate() {
+        let frame = index as u64 / channels;
+        while current < frames.len() && frame >= frames[current].1 {
+            current += 1;
+        }
+        if current == frames.len() {
+            break;
+        }
+        if frame >= frames[current].0 {
+            stats[current].add(sample);
+        }
+    }
+    Ok(stats)
+}
+
+async fn measure_r128(source: &Path, start: Duration, end: Duration) -> Result<(f32, f32), Error> {
+    let output = Command::new("ffmpeg")
+        .arg("-hide_banner")
+        .arg("-nostats")
+        .arg("-ss")
+        .arg(format!("{:.6}", start.as_secs_f64()))
+        .arg("-to")
+        .arg(format!("{:.6}", end.as_secs_f64()))
+        .arg("-i")
+        .arg(source)
+        .arg("-af")
+        .arg("ebur128")
+        .arg("-f")
+        .arg("null")
+        .arg("-")
+        .output()
+        .await?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    match parse_ebur128(&stderr) {
+        Some(r128) if output.status.success() => Ok(r128),
+        _ => Err(Error::new(
+            ErrorKind::Loudness(source.display().to_string()),
+            DebugId::ErrorLoudness,
+        )),
+    }
+}
+
+// integrated loudness and loudness range from the summary `ebur128` prints last
+pub fn parse_ebur128(stderr: &str) -> Option<(f32, f32)> {
+    let summary = &stderr[stderr.rfind("Summary:")?..];
+    let value = |key: &str| -> Option<f32> {
+        summary
+            .lines()
+            .find_map(|l| l.trim().strip_prefix(key))?
+            .split_whitespace()
+            .next()?
+            .parse()
+            .ok()
+    };
+    Some((value("I:")?, value("LRA:")?))
+}
+
+pub fn levels_report(spans: &[AudioSpan]) -> String {
+    let mut report =
+        "file,integrated_lufs,loudness_range_lu,peak_dbfs,rms_dbfs,clipped_samples\n".to_string();
+    for span in spans {
+        if let Some(l) = span.levels() {
+            report.push_str(&format!(
+                "{},{:.1},{:.1},{:.1},{:.1},{}\n",
+                span.name(),
+                l.integrated,
+                l.range,
+                l.peak,
+                l.rms,
+                l.clipped
+            ));
+        }
+    }
+    report
+}
@@ -0,0 +1,159 @@
+use std::{f32::consts::PI, path::PathBuf, time::Duration};
+
+use crate::audio_split::{
+    classify::for_each_window,
+    debug_id::DebugId,
+    error::{Error, ErrorKind},
+};
+
+// long enough to tell the DTMF frequencies apart
+pub const TONE_FRAME: Duration = Duration::from_millis(30);
+// shorter runs of tone frames are part of the recording, not a marker
+const MIN_MARKER: Duration = Duration::from_millis(60);
+const MIN_LEVEL_DB: f32 = -50.0;
+// share of the frame energy in the marker frequencies
+const TONE_RATIO: f32 = 0.6;
+// both DTMF frequencies need a part of it
+const DTMF_MIN_RATIO: f32 = 0.2;
+const DTMF_LOW: [f32; 4] = [697.0, 770.0, 852.0, 941.0];
+const DTMF_HIGH: [f32; 4] = [1209.0, 1336.0, 1477.0, 1633.0];
+const DTMF_DIGITS: [[char; 4]; 4] = [
+    ['1', '2', '3', 'A'],
+    ['4', '5', '6', 'B'],
+    ['7', '8', '9', 'C'],
+    ['*', '0', '#', 'D'],
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarkerTone {
+    // frequency in Hz
+    Sine(f32),
+    // any of these digits
+    Dtmf(Vec<char>),
+}
+
+impl MarkerTone {
+    // a frequency like `1000 Hz`, anything else are DTMF digits like `5` or `0*`
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        let input = input.trim();
+        let upper = input.to_uppercase();
+        if let Some(frequency) = upper.strip_suffix("HZ") {
+            if let Ok(frequency) = frequency.trim().parse::<f32>()
+                && frequency > 0.0
+            {
+                return Ok(MarkerTone::Sine(frequency));
+            }
+        } else {
+            let digits: Vec<char> = upper.chars().collect();
+            if !digits.is_empty()
+                && digits
+                    .iter()
+                    .all(|d| DTMF_DIGITS.as_flattened().contains(d))
+            {
+                return Ok(MarkerTone::Dtmf(digits));
+            }
+        }
+        Err(Error::new(
+            ErrorKind::MarkerTone(input.to_string()),
+            DebugId::ErrorMarkerTone,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Marker {
+    start: Duration,
+    end: Duration,
+}
+
+impl Marker {
+    pub fn start(&self) -> Duration {
+        self.start
+    }
+    pub fn end(&self) -> Duration {
+        self.end
+    }
+    pub fn midpoint(&self) -> Duration {
+        (self.start + self.end) / 2
+    }
+}
+
+// power of a single frequency relative to the energy of the frame, 1.0 for a pure tone
+fn goertzel(frame: &[f32], frequency: f32, rate: f32) -> f32 {
+    let coefficient = 2.0 * (2.0 * PI * frequency / rate).cos();
+    let (mut s1, mut s2) = (0.0, 0.0);
+    for sample in frame {
+        let s0 = sample + coefficient * s1 - s2;
+        (s1, s2) = (s0, s1);
+    }
+    let power = s1 * s1 + s2 * s2 - coefficient * s1 * s2;
+    let energy: f32 = frame.iter().map(|s| s * s).sum();
+    power / (energy * frame.len() as f32 / 2.0)
+}
+
+// the tone heard in the frame, `Some(None)` for a sine marker
+pub fn detect_tone(frame: &[f32], rate: f32, tone: &MarkerTone) -> Option<Option<char>> {
+    let energy: f32 = frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32;
+    if 10.0 * energy.log10() < MIN_LEVEL_DB {
+        return None;
+    }
+    match tone {
+        MarkerTone::Sine(frequency) => {
+            (goertzel(frame, *frequency, rate) > TONE_RATIO).then_some(None)
+        }
+        MarkerTone::Dtmf(digits) => {
+            let strongest = |frequencies: &[f32; 4]| {
+                frequencies
+                    .iter()
+                    .map(|f| goertzel(frame, *f, rate))
+                    .enumerate()
+                    .max_by(|a, b| a.1.total_cmp(&b.1))
+                    .unwrap()
+            };
+            let (row, low) = strongest(&DTMF_LOW);
+            let (column, high) = strongest(&DTMF_HIGH);
+            let digit = DTMF_DIGITS[row][column];
+            (low > DTMF_MIN_RATIO
+                && high > DTMF_MIN_RATIO
+                && low + high > TONE_RATIO
+                && digits.contains(&digit))
+            .then_some(Some(digit))
+        }
+    }
+}
+
+// runs of frames with the same tone, a change of the DTMF digit starts a new marker
+pub fn markers(frames: &[Option<Option<char>>], frame: Duration) -> Vec<Marker> {
+    let mut markers: Vec<Marker> = Vec::new();
+    let mut run: Option<(usize, Option<char>)> = None;
+    for (index, tone) in frames.iter().chain([&None]).enumerate() {
+        match (run, tone) {
+            (Some((_, digit)), Some(current)) if digit == *current => {}
+            _ => {
+                if let Some((first, _)) = run.take() {
+                    let (start, end) = (frame * first as u32, frame * index as u32);
+                    if end - start >= MIN_MARKER {
+                        markers.push(Marker { start, end });
+                    }
+                }
+                run = tone.map(|digit| (index, digit));
+            }
+        }
+    }
+    markers
+}
+
+pub async fn detect_markers(path: PathBuf, tone: MarkerTone) -> Result<Vec<Marker>, Error> {
+    tokio::task::spawn_blocking(move || {
+        let mut frames = Vec::new();
+        // `TONE_FRAME` rounded to whole samples, only the last window may be shorter
+        let mut frame = None;
+        for_each_window(path, TONE_FRAME, |samples, rate| {
+            frame.get_or_insert(Duration::from_secs_f64(samples.len() as f64 / rate as f64));
+            frames.push(detect_tone(samples, rate as f32, &tone))
+        })?;
+        Ok(markers(&frames, frame.unwrap_or(TONE_FRAME)))
+    })
+    .await
+    .unwrap()
+}
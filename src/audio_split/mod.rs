@@ -1,27 +1,80 @@
 pub mod audio_player;
-use std::{num::ParseFloatError, path::PathBuf, time::Duration};
+use std::{
+    num::{ParseFloatError, ParseIntError},
+    ops::RangeInclusive,
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
 
-use iced::{Element, Length, Subscription, Task, alignment::Vertical, widget, window::Event};
+use iced::{
+    Element, Length, Subscription, Task,
+    alignment::Vertical,
+    keyboard::{self, Key, Modifiers, key::Named},
+    widget,
+    window::Event,
+};
 
 use crate::audio_split::{
-    analyze::detect_silence,
+    analyze::{
+        Analysis, AnalysisEvent, Constraints, Defect, DefectKind, NoiseFloor, SegmentLength,
+        Silence, analyze_silence, detect_defects, envelope_silences, equal_parts,
+        estimate_noise_floor, interval_points, level_envelope, measure_depths, snap_to_silences,
+        target_length_cuts,
+    },
     audio::Audio,
     audio_player::AudioPlayer,
     audio_span::AudioSpan,
+    classify::{ClassRegion, SoundClass, class_changes, classify_audio},
     error::Error,
+    export::{ExportOptions, FadeCurve},
+    looping::Looping,
+    loudness::{Levels, Normalize, NormalizeMode, SegmentGain, measure_levels},
+    markers::{Marker, MarkerTone, detect_markers},
+    preview::{Preview, PreviewStep},
+    settings::{Preset, Settings},
+    shortcuts::{Action, KeyBindings},
+    tracks::detect_track_boundaries,
     user_info::{UserInfo, info, warning},
-    utils::{open_audio_file, open_audio_file_dialog, open_export_folder_dialog, save_audio_files},
+    utils::{
+        LOUDNESS_REPORT, load_key_bindings, load_settings, open_audio_file, open_audio_file_dialog,
+        open_export_folder_dialog, save_audio_files, save_settings,
+    },
 };
 mod analyze;
 mod audio;
 mod audio_span;
 mod canvas;
+mod classify;
 mod debug_id;
 pub mod error;
+mod export;
+mod looping;
+mod loudness;
+mod markers;
+mod preview;
+mod selection;
+mod settings;
+mod shortcuts;
+mod tracks;
 mod user_info;
 mod utils;
 
+pub use analyze::{Envelope, SplitMode};
 pub use debug_id::DebugId;
+pub use selection::Selection;
+
+// playback wraps to zero this close to the end, before the source runs out
+const END_MARGIN: Duration = Duration::from_millis(300);
+const INPUT_WIDTH: f32 = 80.0;
+const SLIDER_WIDTH: f32 = 120.0;
+const THRESHOLD_SLIDER: RangeInclusive<f32> = -80.0..=0.0;
+const DURATION_SLIDER: RangeInclusive<f32> = 0.1..=5.0;
+const SEEK_STEP: Duration = Duration::from_secs(5);
+// jumping back while playing should not land on the split point just passed
+const JUMP_BACK_TOLERANCE: Duration = Duration::from_millis(500);
+// how far a split point may be from the playhead and still count as under it
+const PLAYHEAD_TOLERANCE: Duration = Duration::from_millis(500);
 
 #[derive(Debug)]
 pub struct AudioSplit<P: AudioPlayer> {
@@ -29,38 +82,119 @@ pub struct AudioSplit<P: AudioPlayer> {
     audio: Option<Audio>,
     is_playing: bool,
     import_path: Option<PathBuf>,
+    import_folder: Option<PathBuf>,
     export_path: Option<PathBuf>,
     threshold: String,
     duration: String,
     extension: Option<PathBuf>,
     info: UserInfo,
     undo_stack: Vec<Message>,
+    redo_stack: Vec<Message>,
+    preview: Option<Preview>,
+    preview_duration: String,
+    preview_gap: bool,
+    looping: Option<Looping>,
+    loop_start: Option<Duration>,
+    key_bindings: KeyBindings,
+    show_help: bool,
+    presets: Vec<Preset>,
+    persist_settings: bool,
+    selected_spans: Vec<u32>,
+    trim_silence: bool,
+    trim_padding: String,
+    fade_in: String,
+    fade_out: String,
+    fade_curve: FadeCurve,
+    snap_to_zero_crossing: bool,
+    normalize: bool,
+    loudness_target: String,
+    true_peak: String,
+    normalize_mode: NormalizeMode,
+    min_segment_length: String,
+    max_segments: String,
+    split_mode: SplitMode,
+    interval: String,
+    parts: String,
+    snap_window: String,
+    min_score: String,
+    nth: String,
+    analysis: Option<Analysis>,
+    analyze_from: String,
+    analyze_to: String,
+    target_length_mode: bool,
+    target_length: String,
+    min_length: String,
+    max_length: String,
+    marker_tone: String,
+    remove_markers: bool,
 }
 
 impl<P: AudioPlayer> AudioSplit<P> {
     pub fn init(audio_player: P) -> Self {
+        let settings = Settings::default();
         Self {
             audio_player,
             audio: None,
             is_playing: false,
             import_path: None,
-            export_path: None,
-            duration: "0.3".to_string(),
-            threshold: "-45.0".to_string(),
+            import_folder: settings.import_folder,
+            export_path: settings.export_folder,
+            duration: settings.duration,
+            threshold: settings.threshold,
             extension: None,
             info: UserInfo::None,
             undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            preview: None,
+            preview_duration: settings.preview_duration,
+            preview_gap: settings.preview_gap,
+            looping: None,
+            loop_start: None,
+            key_bindings: KeyBindings::default(),
+            show_help: false,
+            presets: settings.presets,
+            persist_settings: false,
+            selected_spans: Vec::new(),
+            trim_silence: settings.trim_silence,
+            trim_padding: settings.trim_padding,
+            fade_in: settings.fade_in,
+            fade_out: settings.fade_out,
+            fade_curve: settings.fade_curve,
+            snap_to_zero_crossing: settings.snap_to_zero_crossing,
+            normalize: settings.normalize,
+            loudness_target: settings.loudness_target,
+            true_peak: settings.true_peak,
+            normalize_mode: settings.normalize_mode,
+            min_segment_length: settings.min_segment_length,
+            max_segments: settings.max_segments,
+            split_mode: settings.split_mode,
+            interval: settings.interval,
+            parts: settings.parts,
+            snap_window: settings.snap_window,
+            min_score: settings.min_score,
+            nth: "2".to_string(),
+            analysis: None,
+            analyze_from: String::new(),
+            analyze_to: String::new(),
+            target_length_mode: settings.target_length_mode,
+            target_length: settings.target_length,
+            min_length: settings.min_length,
+            max_length: settings.max_length,
+            marker_tone: settings.marker_tone,
+            remove_markers: settings.remove_markers,
         }
     }
+    pub fn load_config(&self) -> Task<Message> {
+        Task::batch([
+            Task::perform(load_key_bindings(), Message::KeyBindingsLoaded),
+            Task::perform(load_settings(), Message::SettingsLoaded),
+        ])
+    }
     pub fn update(&mut self, message: Message) -> Task<Message> {
         self.add_to_undo_stack(&message);
         match message {
             Message::OpenAudioFileDialog => Task::perform(
-                open_audio_file_dialog(
-                    self.import_path
-                        .clone()
-                        .and_then(|p| p.parent().map(|p| p.to_path_buf())),
-                ),
+                open_audio_file_dialog(self.import_folder.clone()),
                 Message::AudioFilePathLoaded,
             ),
             Message::OpenExportDialog => {
@@ -76,36 +210,40 @@ impl<P: AudioPlayer> AudioSplit<P> {
             }
             Message::AudioFilePathLoaded(path) => {
                 if let Some(path) = path {
-                    self.import_path = Some(path.clone().into());
-                    self.extension = PathBuf::from(path.clone()).extension().map(|s| s.into());
-                    Task::perform(
-                        open_audio_file(path, self.audio_player.get_player()),
-                        Message::AudioLoaded,
-                    )
+                    self.load_audio_file(path.into())
                 } else {
                     Task::none()
                 }
             }
             Message::ExportPathLoaded(path) => {
                 if let Some(path) = path
+                    && let Some(options) = self.export_options()
                     && let Some(audio) = &self.audio
                 {
-                    self.export_path = Some(path.clone().into());
-                    Task::perform(
+                    let task = Task::perform(
                         save_audio_files(
                             self.import_path.clone().unwrap(),
-                            path.into(),
+                            path.clone().into(),
                             self.extension.clone().unwrap(),
-                            audio.spans().to_vec(),
+                            audio
+                                .spans()
+                                .iter()
+                                .filter(|s| !s.is_excluded())
+                                .cloned()
+                                .collect(),
+                            audio.silences().to_vec(),
+                            options,
                         ),
                         Message::AudioSaved,
-                    )
+                    );
+                    self.export_path = Some(path.into());
+                    Task::batch([task, self.save_settings()])
                 } else {
                     Task::none()
                 }
             }
             Message::AudioSaved(r) => {
-                r.unwrap();
+                self.apply_result_and(r, |this, gains| this.report_gains(&gains));
                 Task::none()
             }
             Message::AudioLoaded(audio) => {
@@ -119,9 +257,42 @@ impl<P: AudioPlayer> AudioSplit<P> {
             Message::Tick => {
                 if let Some(audio) = self.audio.as_mut() {
                     audio.update_position_info();
-                    if let Some(last) = audio.spans().last()
-                        && let Some(sub) = (last.end().checked_sub(audio.get_pos()))
-                        && sub.as_millis() < 300
+                    if let Some(preview) = self.preview.as_mut() {
+                        match preview.step(audio.get_pos()) {
+                            PreviewStep::Continue => {}
+                            PreviewStep::Pause => audio.set_pause(),
+                            PreviewStep::Resume => audio.set_play(),
+                            PreviewStep::Finish {
+                                return_to,
+                                was_playing,
+                            } => {
+                                audio.seek(return_to);
+                                if !was_playing {
+                                    audio.set_pause();
+                                }
+                                self.is_playing = was_playing;
+                                self.preview = None;
+                            }
+                        }
+                    }
+                    if self.preview.is_some() {
+                        // the preview returns to the previous position on its own
+                    } else if let Some(looping) = self.looping {
+                        if let Some((start, end)) = looping.range(audio) {
+                            let end = match audio.playback_end() {
+                                Some(last) => end.min(last.saturating_sub(END_MARGIN)),
+                                None => end,
+                            };
+                            let pos = audio.get_pos();
+                            if pos < start || pos >= end {
+                                audio.seek(start);
+                            }
+                        } else {
+                            self.looping = None;
+                        }
+                    } else if let Some(last) = audio.playback_end()
+                        && let Some(sub) = (last.checked_sub(audio.get_pos()))
+                        && sub < END_MARGIN
                     {
                         audio.set_pos(0, 0.0);
                     }
@@ -130,6 +301,7 @@ impl<P: AudioPlayer> AudioSplit<P> {
             }
             Message::Pause => {
                 self.is_playing = false;
+                self.preview = None;
                 if let Some(audio) = self.audio.as_mut() {
                     audio.set_pause();
                 } else {
@@ -139,6 +311,7 @@ impl<P: AudioPlayer> AudioSplit<P> {
             }
             Message::Play => {
                 self.is_playing = true;
+                self.preview = None;
                 if let Some(audio) = self.audio.as_mut() {
                     audio.set_play();
                 } else {
@@ -150,13 +323,25 @@ impl<P: AudioPlayer> AudioSplit<P> {
                 if let Some(audio) = &mut self.audio {
                     audio.reset();
                 }
-                let _rem = self.undo_stack.pop();
+                if let Some(message) = self.undo_stack.pop() {
+                    self.redo_stack.push(message);
+                }
                 self.replay_messages();
                 if let Some(player) = &mut self.audio {
                     player.update_position_info();
                 }
                 Task::none()
             }
+            Message::Redo => {
+                if let Some(message) = self.redo_stack.pop() {
+                    let redo_stack = std::mem::take(&mut self.redo_stack);
+                    let task = self.update(message);
+                    self.redo_stack = redo_stack;
+                    task
+                } else {
+                    Task::none()
+                }
+            }
             Message::Split => {
                 if let Some(audio) = self.audio.as_mut() {
                     if audio.split_points_selected() {
@@ -180,6 +365,91 @@ impl<P: AudioPlayer> AudioSplit<P> {
                 if let Some(audio) = self.audio.as_mut() {
                     audio.delete_span(id);
                 }
+                if self.looping.and_then(|l| l.span()) == Some(id) {
+                    self.looping = None;
+                }
+                Task::none()
+            }
+            Message::RestoreAudioSpan(id) => {
+                if let Some(audio) = self.audio.as_mut() {
+                    audio.restore_span(id);
+                }
+                Task::none()
+            }
+            Message::TrimSpan(id, start, end) => {
+                if let Some(audio) = self.audio.as_mut()
+                    && !audio.trim_span(id, start, end)
+                {
+                    self.set_warning(
+                        warning::INVALID_TRIM_RANGE,
+                        DebugId::WarningInvalidTrimRange,
+                    );
+                }
+                Task::none()
+            }
+            Message::UpdateSpanStartInput(id, text) => {
+                if let Some(audio) = self.audio.as_mut() {
+                    audio.update_span_range_input(id, Some(text), None);
+                }
+                Task::none()
+            }
+            Message::UpdateSpanEndInput(id, text) => {
+                if let Some(audio) = self.audio.as_mut() {
+                    audio.update_span_range_input(id, None, Some(text));
+                }
+                Task::none()
+            }
+            Message::SubmitSpanRange(id) => {
+                let Some(span) = self
+                    .audio
+                    .as_ref()
+                    .and_then(|a| a.spans().iter().find(|s| s.id() == id))
+                else {
+                    return Task::none();
+                };
+                let (start, end) = (span.start(), span.end());
+                let (start_input, end_input) = span.edited_range_input();
+                let (start_input, end_input) = (
+                    start_input.map(str::to_string),
+                    end_input.map(str::to_string),
+                );
+                if start_input.is_none() && end_input.is_none() {
+                    return Task::none();
+                }
+                let mut parse = |input: Option<String>, current: Duration| match input {
+                    Some(input) => self
+                        .apply_result(input.trim().parse().map_err(|e: ParseFloatError| e.into()))
+                        .filter(|secs: &f64| self.check_duration(*secs as f32))
+                        .map(Duration::from_secs_f64),
+                    None => Some(current),
+                };
+                match (parse(start_input, start), parse(end_input, end)) {
+                    (Some(start), Some(end)) => self.update(Message::TrimSpan(id, start, end)),
+                    _ => Task::none(),
+                }
+            }
+            Message::MergeWithNext(id) => {
+                let merged = self.audio.as_mut().map(|a| a.merge_with_next(id));
+                self.report_merge(merged);
+                Task::none()
+            }
+            Message::MergeWithPrevious(id) => {
+                let merged = self.audio.as_mut().map(|a| a.merge_with_previous(id));
+                self.report_merge(merged);
+                Task::none()
+            }
+            Message::MergeSpans(ids) => {
+                let merged = self.audio.as_mut().map(|a| a.merge_spans(&ids));
+                self.selected_spans.clear();
+                self.report_merge(merged);
+                Task::none()
+            }
+            Message::ToggleSpanSelection(id) => {
+                if let Some(i) = self.selected_spans.iter().position(|s| *s == id) {
+                    self.selected_spans.remove(i);
+                } else {
+                    self.selected_spans.push(id);
+                }
                 Task::none()
             }
             Message::SpanTextUpdate(id, text) => {
@@ -189,15 +459,47 @@ impl<P: AudioPlayer> AudioSplit<P> {
                 Task::none()
             }
             Message::WindowEvent(e) => match e {
-                Event::FileDropped(f) => {
-                    self.import_path = Some(f.clone());
-                    Task::perform(
-                        open_audio_file(f, self.audio_player.get_player()),
-                        Message::AudioLoaded,
-                    )
-                }
+                Event::FileDropped(f) => self.load_audio_file(f),
                 _ => Task::none(),
             },
+            Message::Analyze
+                if matches!(self.split_mode, SplitMode::Interval | SplitMode::EqualParts) =>
+            {
+                match self.generated_split_points() {
+                    Some(points) => Task::batch([
+                        Task::done(Message::SplitPointsGenerated(points)),
+                        self.save_settings(),
+                    ]),
+                    None => Task::none(),
+                }
+            }
+            Message::Analyze if self.split_mode == SplitMode::TrackBoundaries => {
+                if let Some(path) = self.import_path.clone() {
+                    Task::batch([
+                        Task::perform(
+                            detect_track_boundaries(path),
+                            Message::TrackBoundariesDetected,
+                        ),
+                        self.save_settings(),
+                    ])
+                } else {
+                    self.set_warning(warning::NO_AUDIO_LOADED, DebugId::WarningNoAudioLoaded);
+                    Task::none()
+                }
+            }
+            Message::Analyze if self.split_mode == SplitMode::ToneMarkers => {
+                let Some(path) = self.import_path.clone() else {
+                    self.set_warning(warning::NO_AUDIO_LOADED, DebugId::WarningNoAudioLoaded);
+                    return Task::none();
+                };
+                match self.apply_result(MarkerTone::parse(&self.marker_tone)) {
+                    Some(tone) => Task::batch([
+                        Task::perform(detect_markers(path, tone), Message::MarkersDetected),
+                        self.save_settings(),
+                    ]),
+                    None => Task::none(),
+                }
+            }
             Message::Analyze => {
                 if let Some(path) = self.import_path.clone() {
                     if let Some(duration) = self
@@ -208,11 +510,21 @@ impl<P: AudioPlayer> AudioSplit<P> {
                                 .map_err(|e: ParseFloatError| e.into()),
                         )
                         && self.check_duration(duration)
+                        && self.constraints().is_some()
+                        && (self.split_mode != SplitMode::SnappedInterval
+                            || self.snap_interval().is_some())
+                        && (!self.target_length_mode || self.segment_length().is_some())
                     {
-                        Task::perform(
-                            detect_silence(path, threshold, Duration::from_secs_f32(duration)),
-                            Message::Analyzed,
-                        )
+                        Task::batch([
+                            self.start_analysis(
+                                path.clone(),
+                                threshold,
+                                Duration::from_secs_f32(duration),
+                                None,
+                            ),
+                            Task::perform(detect_defects(path), Message::DefectsDetected),
+                            self.save_settings(),
+                        ])
                     } else {
                         Task::none()
                     }
@@ -222,15 +534,57 @@ impl<P: AudioPlayer> AudioSplit<P> {
                 }
             }
             Message::Analyzed(s) => {
-                if let Some(audio) = self.audio.as_mut() {
-                    let split_points = s.unwrap();
+                let constraints = self.constraints().unwrap_or_default();
+                let snap = match self.split_mode {
+                    SplitMode::SnappedInterval => self.snap_interval(),
+                    _ => None,
+                };
+                if let Some(detected) = self.apply_result(s).map(|s| self.with_depths(s))
+                    && let Some(audio) = self.audio.as_mut()
+                {
+                    let detected_len = detected.len();
+                    let silences = constraints.apply(detected, audio.duration());
+                    let split_points: Vec<Duration> = match snap {
+                        Some((interval, window)) => snap_to_silences(
+                            &interval_points(audio.duration(), interval),
+                            &silences,
+                            window,
+                        ),
+                        None => silences.iter().map(Silence::midpoint).collect(),
+                    };
                     let len = split_points.len();
                     Audio::set_split_points(audio.spans_mut(), split_points);
+                    audio.set_scores(silences.iter().map(|s| (s.midpoint(), s.score())).collect());
+                    let duration = audio.duration();
+                    audio.set_silences(silences.clone());
                     if len == 0 {
                         self.set_warning(
                             warning::NO_SPLIT_POINTS_FOUND,
                             DebugId::WarningNoSplitPointFound,
                         );
+                    } else if snap.is_some() {
+                        self.set_info(
+                            info::SPLIT_POINTS_GENERATED.replace("{}", &len.to_string()),
+                            DebugId::InfoSplitPointsGenerated(len),
+                        );
+                    } else if self.target_length_mode
+                        && let Some(length) = self.segment_length()
+                    {
+                        let cuts = target_length_cuts(&silences, duration, &length);
+                        self.set_info(
+                            info::TARGET_LENGTH_SELECTED
+                                .replacen("{}", &cuts.len().to_string(), 1)
+                                .replacen("{}", &len.to_string(), 1),
+                            DebugId::InfoTargetLengthSelected(cuts.len()),
+                        );
+                        return Task::done(Message::SetSelectedSplitPoints(cuts));
+                    } else if len < detected_len {
+                        self.set_info(
+                            info::SPLIT_POINTS_CONSTRAINED
+                                .replacen("{}", &len.to_string(), 1)
+                                .replacen("{}", &(detected_len - len).to_string(), 1),
+                            DebugId::InfoSplitPointsConstrained(len),
+                        );
                     } else {
                         self.set_info(
                             info::SPLIT_POINTS_DETECTED.replace("{}", &len.to_string()),
@@ -240,130 +594,1357 @@ impl<P: AudioPlayer> AudioSplit<P> {
                 }
                 Task::none()
             }
-            Message::ClickSplitPoint(split_point) => {
+            Message::AnalysisProgress(event) => {
+                let Some(analysis) = self.analysis.as_mut() else {
+                    return Task::none();
+                };
+                match event {
+                    AnalysisEvent::Progress(position) => analysis.set_position(position),
+                    AnalysisEvent::Found(silence) => {
+                        analysis.add_found();
+                        if let Some(audio) = self.audio.as_mut() {
+                            audio.insert_split_point(silence.midpoint());
+                        }
+                    }
+                    AnalysisEvent::Finished(silences) => {
+                        let range = analysis.range();
+                        self.analysis = None;
+                        return self.update(match range {
+                            Some((start, end)) => Message::RangeAnalyzed(start, end, silences),
+                            None => Message::Analyzed(silences),
+                        });
+                    }
+                }
+                Task::none()
+            }
+            Message::CancelAnalysis => {
+                if let Some(analysis) = self.analysis.take() {
+                    analysis.cancel();
+                    // drops the split points shown while analyzing
+                    if let Some(audio) = &mut self.audio {
+                        audio.reset();
+                    }
+                    self.replay_messages();
+                    if let Some(audio) = &mut self.audio {
+                        audio.update_position_info();
+                    }
+                    self.set_info(info::ANALYSIS_CANCELLED, DebugId::InfoAnalysisCancelled);
+                }
+                Task::none()
+            }
+            Message::AnalyzeSpan(id) => {
+                match self
+                    .audio
+                    .as_ref()
+                    .and_then(|a| a.spans().iter().find(|s| s.id() == id))
+                {
+                    Some(span) => self.update(Message::AnalyzeRange(span.start(), span.end())),
+                    None => Task::none(),
+                }
+            }
+            Message::UpdateAnalyzeFrom(s) => {
+                self.analyze_from = s;
+                Task::none()
+            }
+            Message::UpdateAnalyzeTo(s) => {
+                self.analyze_to = s;
+                Task::none()
+            }
+            Message::AnalyzeInputRange => {
+                if let Some(from) = self.apply_result(
+                    self.analyze_from
+                        .parse()
+                        .map_err(|e: ParseFloatError| e.into()),
+                ) && let Some(to) = self.apply_result(
+                    self.analyze_to
+                        .parse()
+                        .map_err(|e: ParseFloatError| e.into()),
+                ) && self.check_duration(from)
+                    && self.check_duration(to)
+                {
+                    self.update(Message::AnalyzeRange(
+                        Duration::from_secs_f32(from),
+                        Duration::from_secs_f32(to),
+                    ))
+                } else {
+                    Task::none()
+                }
+            }
+            Message::AnalyzeRange(start, end) => {
+                let Some(path) = self.import_path.clone() else {
+                    self.set_warning(warning::NO_AUDIO_LOADED, DebugId::WarningNoAudioLoaded);
+                    return Task::none();
+                };
+                if self
+                    .audio
+                    .as_ref()
+                    .is_none_or(|a| end <= start || a.duration() < end)
+                {
+                    self.set_warning(
+                        warning::INVALID_ANALYSIS_RANGE,
+                        DebugId::WarningInvalidAnalysisRange,
+                    );
+                    return Task::none();
+                }
+                if let Some(duration) =
+                    self.apply_result(self.duration.parse().map_err(|e: ParseFloatError| e.into()))
+                    && let Some(threshold) = self.apply_result(
+                        self.threshold
+                            .parse()
+                            .map_err(|e: ParseFloatError| e.into()),
+                    )
+                    && self.check_duration(duration)
+                {
+                    Task::batch([
+                        self.start_analysis(
+                            path,
+                            threshold,
+                            Duration::from_secs_f32(duration),
+                            Some((start, end)),
+                        ),
+                        self.save_settings(),
+                    ])
+                } else {
+                    Task::none()
+                }
+            }
+            Message::RangeAnalyzed(start, end, silences) => {
+                self.apply_result_and(silences, |this, silences| {
+                    let silences = this.with_depths(silences);
+                    if let Some(audio) = this.audio.as_mut() {
+                        let len = silences.len();
+                        audio.replace_split_points_in(start, end, silences);
+                        this.set_info(
+                            info::RANGE_ANALYZED
+                                .replacen("{}", &len.to_string(), 1)
+                                .replacen("{}", &format!("{:.2}", start.as_secs_f32()), 1)
+                                .replacen("{}", &format!("{:.2}", end.as_secs_f32()), 1),
+                            DebugId::InfoRangeAnalyzed(len),
+                        );
+                    }
+                });
+                Task::none()
+            }
+            Message::AnalyzeLevels => {
+                if let Some(path) = self.import_path.clone()
+                    && let Some(audio) = &self.audio
+                {
+                    let spans = audio
+                        .spans()
+                        .iter()
+                        .filter(|s| !s.is_excluded())
+                        .map(|s| (s.id(), s.start(), s.end()))
+                        .collect();
+                    Task::perform(measure_levels(path, spans), Message::LevelsMeasured)
+                } else {
+                    self.set_warning(warning::NO_AUDIO_LOADED, DebugId::WarningNoAudioLoaded);
+                    Task::none()
+                }
+            }
+            Message::LevelsMeasured(levels) => {
+                self.apply_result_and(levels, |this, levels| {
+                    let measured = levels.len();
+                    let clipping = levels.iter().filter(|(_, l)| l.is_clipping()).count();
+                    if let Some(audio) = this.audio.as_mut() {
+                        audio.set_levels(levels);
+                    }
+                    this.set_info(
+                        info::LEVELS_MEASURED
+                            .replacen("{}", &measured.to_string(), 1)
+                            .replacen("{}", &clipping.to_string(), 1),
+                        DebugId::InfoLevelsMeasured,
+                    );
+                });
+                Task::none()
+            }
+            Message::DefectsDetected(defects) => {
+                self.apply_result_and(defects, |this, defects| {
+                    if let Some(audio) = this.audio.as_mut() {
+                        audio.set_defects(defects);
+                    }
+                });
+                Task::none()
+            }
+            Message::Classify => {
+                if let Some(path) = self.import_path.clone() {
+                    Task::perform(classify_audio(path), Message::Classified)
+                } else {
+                    self.set_warning(warning::NO_AUDIO_LOADED, DebugId::WarningNoAudioLoaded);
+                    Task::none()
+                }
+            }
+            Message::Classified(regions) => {
+                self.apply_result_and(regions, |this, regions| {
+                    let count = |class| regions.iter().filter(|r| r.class() == class).count();
+                    this.set_info(
+                        info::CLASSIFIED
+                            .replacen("{}", &regions.len().to_string(), 1)
+                            .replacen("{}", &count(SoundClass::Speech).to_string(), 1)
+                            .replacen("{}", &count(SoundClass::Music).to_string(), 1)
+                            .replacen("{}", &count(SoundClass::Noise).to_string(), 1),
+                        DebugId::InfoClassified(regions.len()),
+                    );
+                    if let Some(audio) = this.audio.as_mut() {
+                        audio.set_regions(regions);
+                    }
+                });
+                Task::none()
+            }
+            Message::SplitAtClassChanges => {
+                let changes = self
+                    .audio
+                    .as_ref()
+                    .map(|a| class_changes(a.regions()))
+                    .unwrap_or_default();
+                if changes.is_empty() {
+                    self.set_warning(warning::NOT_CLASSIFIED, DebugId::WarningNotClassified);
+                    Task::none()
+                } else {
+                    self.update(Message::SplitPointsGenerated(changes))
+                }
+            }
+            Message::JumpToDefect(pos) => {
                 if let Some(audio) = self.audio.as_mut() {
-                    audio.toggle_selected_split_points(split_point);
+                    audio.seek(pos);
                 }
                 Task::none()
             }
-            Message::UpdateDuration(s) => {
-                self.duration = s;
+            Message::TrackBoundariesDetected(boundaries) => {
+                self.apply_result_and(boundaries, |this, boundaries| {
+                    let Some(audio) = this.audio.as_mut() else {
+                        return;
+                    };
+                    let len = boundaries.len();
+                    Audio::set_split_points(
+                        audio.spans_mut(),
+                        boundaries.iter().map(|(p, _)| *p).collect(),
+                    );
+                    audio.set_silences(Vec::new());
+                    audio.set_scores(boundaries);
+                    if len == 0 {
+                        this.set_warning(
+                            warning::NO_SPLIT_POINTS_FOUND,
+                            DebugId::WarningNoSplitPointFound,
+                        );
+                    } else {
+                        this.set_info(
+                            info::TRACK_BOUNDARIES_DETECTED.replace("{}", &len.to_string()),
+                            DebugId::InfoTrackBoundaries(len),
+                        );
+                    }
+                });
                 Task::none()
             }
-            Message::UpdateThreshold(s) => {
-                self.threshold = s;
+            Message::UpdateMarkerTone(s) => {
+                self.marker_tone = s;
                 Task::none()
             }
-        }
-    }
-    fn add_to_undo_stack(&mut self, message: &Message) {
-        match message {
-            Message::AudioLoaded(..) => self.undo_stack.clear(),
-
-            x @ Message::UpdateDuration(..)
-            | x @ Message::UpdateThreshold(..)
-            | x @ Message::Analyzed(..)
-            | x @ Message::DeleteAudioSpan(..)
-            | x @ Message::SpanTextUpdate(..)
-            | x @ Message::ClickSplitPoint(..)
-            | x @ Message::Split => self.undo_stack.push(x.clone()),
-            _ => {}
-        }
-    }
-    pub fn view(&self) -> Element<'_, Message> {
-        widget::column![self.view_top(), self.view_center(), self.view_info()].into()
-    }
-    fn view_top(&self) -> Element<'_, Message> {
-        widget::row![
-            widget::container(
-                widget::button("open audio file").on_press(Message::OpenAudioFileDialog)
-            )
-            .id(DebugId::ButtonOpen),
-            if self.is_playing {
-                widget::container(widget::button("pause").on_press(Message::Pause))
-                    .id(DebugId::ButtonPause)
-            } else {
-                widget::container(widget::button("play").on_press(Message::Play))
-                    .id(DebugId::ButtonPlay)
-            },
-            widget::text("threshold in dB:"),
-            widget::text_input("", &self.threshold)
-                .on_input(Message::UpdateThreshold)
-                .id(DebugId::TextInputThreshold),
-            widget::text("duration in sec:"),
-            widget::text_input("", &self.duration)
-                .on_input(Message::UpdateDuration)
-                .id(DebugId::TextInputDuration),
-            widget::container(widget::button("undo").on_press(Message::Undo))
-                .id(DebugId::ButtonUndo),
-            widget::container(widget::button("analyze").on_press(Message::Analyze))
-                .id(DebugId::ButtonAnalyze),
-            widget::container(widget::button("split").on_press(Message::Split))
-                .id(DebugId::ButtonSplit),
-            widget::container(widget::button("export").on_press(Message::OpenExportDialog))
-                .id(DebugId::ButtonExport),
-        ]
-        .spacing(5)
-        .align_y(Vertical::Center)
-        .into()
-    }
-    fn view_center(&self) -> Element<'_, Message> {
-        if let Some(audio) = &self.audio {
-            widget::container(widget::column![
-                widget::space().height(Length::FillPortion(2)),
-                audio.view(),
-                widget::space().height(Length::FillPortion(3)),
-            ])
-        } else {
-            widget::container(widget::text("Please open an audio file"))
-        }
-        .center(Length::Fill)
-        .into()
-    }
-    fn view_info(&self) -> Element<'_, Message> {
-        match &self.info {
-            UserInfo::None => widget::container(widget::space()),
-            UserInfo::Info(text, id) => {
-                widget::container(widget::text(text).style(widget::text::base)).id(*id)
+            Message::ToggleRemoveMarkers(remove) => {
+                self.remove_markers = remove;
+                Task::none()
             }
-            UserInfo::Waring(text, id) => {
-                widget::container(widget::text(text).style(widget::text::warning)).id(*id)
+            Message::MarkersDetected(markers) => {
+                let Some(markers) = self.apply_result(markers) else {
+                    return Task::none();
+                };
+                let Some(duration) = self.audio.as_ref().map(Audio::duration) else {
+                    return Task::none();
+                };
+                if markers.is_empty() {
+                    self.set_warning(warning::NO_MARKERS_FOUND, DebugId::WarningNoMarkersFound);
+                    return Task::none();
+                }
+                // the split and delete messages below are recorded for undo one by one
+                let mut tasks = Vec::new();
+                if self.remove_markers {
+                    let mut edges: Vec<Duration> = markers
+                        .iter()
+                        .flat_map(|m| [m.start(), m.end()])
+                        .filter(|p| !p.is_zero() && *p < duration)
+                        .collect();
+                    edges.dedup();
+                    tasks.push(self.update(Message::SplitPointsGenerated(edges.clone())));
+                    tasks.push(self.update(Message::SetSelectedSplitPoints(edges)));
+                    tasks.push(self.update(Message::Split));
+                    let marker_spans: Vec<u32> = self
+                        .audio
+                        .as_ref()
+                        .map(|audio| {
+                            audio
+                                .spans()
+                                .iter()
+                                .filter(|s| {
+                                    markers
+                                        .iter()
+                                        .any(|m| m.start() <= s.start() && s.end() <= m.end())
+                                })
+                                .map(AudioSpan::id)
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    for id in marker_spans {
+                        tasks.push(self.update(Message::DeleteAudioSpan(id)));
+                    }
+                } else {
+                    tasks.push(self.update(Message::SplitPointsGenerated(
+                        markers.iter().map(Marker::midpoint).collect(),
+                    )));
+                }
+                self.set_info(
+                    info::MARKERS_DETECTED.replace("{}", &markers.len().to_string()),
+                    DebugId::InfoMarkersDetected(markers.len()),
+                );
+                Task::batch(tasks)
             }
-            UserInfo::Error(e) => {
-                widget::container(widget::text(format!("{e}")).style(widget::text::danger))
-                    .id(e.id())
+            Message::SplitPointsGenerated(split_points) => {
+                if let Some(audio) = self.audio.as_mut() {
+                    let len = split_points.len();
+                    Audio::set_split_points(audio.spans_mut(), split_points);
+                    audio.set_silences(Vec::new());
+                    audio.set_scores(Vec::new());
+                    self.set_info(
+                        info::SPLIT_POINTS_GENERATED.replace("{}", &len.to_string()),
+                        DebugId::InfoSplitPointsGenerated(len),
+                    );
+                }
+                Task::none()
             }
-        }
-        .padding(5)
-        .into()
-    }
-    pub fn subscription(&self) -> Subscription<Message> {
-        let tick = iced::time::every(Duration::from_millis(16)).map(|_| Message::Tick);
-        Subscription::batch([
-            tick,
-            iced::window::events().map(|f| Message::WindowEvent(f.1)),
-        ])
-    }
-    pub fn set_audio(&mut self, audio: Audio) {
-        self.audio = Some(audio);
-        self.is_playing = true;
-        self.set_info(info::AUDIO_LOADED, DebugId::InfoAudioLoaded);
-    }
-    fn apply_result<T>(&mut self, value: Result<T, Error>) -> Option<T> {
-        match value {
-            Ok(v) => Some(v),
-            Err(e) => {
-                self.info = UserInfo::Error(e);
-                None
+            Message::SelectSplitMode(mode) => {
+                self.split_mode = mode;
+                Task::none()
             }
-        }
-    }
-    fn apply_result_and<T>(&mut self, value: Result<T, Error>, mut then: impl FnMut(&mut Self, T)) {
-        match value {
-            Ok(v) => then(self, v),
+            Message::UpdateInterval(s) => {
+                self.interval = s;
+                Task::none()
+            }
+            Message::UpdateParts(s) => {
+                self.parts = s;
+                Task::none()
+            }
+            Message::UpdateSnapWindow(s) => {
+                self.snap_window = s;
+                Task::none()
+            }
+            Message::BulkSelect(selection) => {
+                let Some(audio) = &self.audio else {
+                    self.set_warning(warning::NO_AUDIO_LOADED, DebugId::WarningNoAudioLoaded);
+                    return Task::none();
+                };
+                let split_points =
+                    selection.apply(&audio.all_split_points(), &audio.selected_split_points());
+                self.set_info(
+                    info::SPLIT_POINTS_SELECTED.replace("{}", &split_points.len().to_string()),
+                    DebugId::InfoSelection(split_points.len()),
+                );
+                self.update(Message::SetSelectedSplitPoints(split_points))
+            }
+            Message::UpdateNth(s) => {
+                self.nth = s;
+                Task::none()
+            }
+            Message::SelectEveryNth => {
+                match self
+                    .apply_result(self.nth.trim().parse().map_err(|e: ParseIntError| e.into()))
+                {
+                    Some(n) => self.update(Message::BulkSelect(Selection::EveryNth(n))),
+                    None => Task::none(),
+                }
+            }
+            Message::UpdateMinScore(s) => {
+                self.min_score = s;
+                Task::none()
+            }
+            Message::SelectAboveScore => {
+                let Some(audio) = &self.audio else {
+                    self.set_warning(warning::NO_AUDIO_LOADED, DebugId::WarningNoAudioLoaded);
+                    return Task::none();
+                };
+                // snapped intervals keep the scores of all silences, not only the split points
+                let candidates = audio.all_split_points();
+                let scores = audio.scores().to_vec();
+                let Some(min_score) = self.parse_percent(self.min_score.clone()) else {
+                    return Task::none();
+                };
+                let split_points: Vec<Duration> = scores
+                    .into_iter()
+                    .filter(|(p, score)| *score * 100.0 >= min_score && candidates.contains(p))
+                    .map(|(p, _)| p)
+                    .collect();
+                self.set_info(
+                    info::SELECTED_ABOVE_SCORE
+                        .replacen("{}", &split_points.len().to_string(), 1)
+                        .replacen("{}", &self.min_score, 1),
+                    DebugId::InfoSelectedAboveScore(split_points.len()),
+                );
+                self.update(Message::SetSelectedSplitPoints(split_points))
+            }
+            Message::SetSelectedSplitPoints(split_points) => {
+                if let Some(audio) = self.audio.as_mut() {
+                    audio.set_selected_split_points(&split_points);
+                }
+                Task::none()
+            }
+            Message::ToggleTargetLengthMode(mode) => {
+                self.target_length_mode = mode;
+                Task::none()
+            }
+            Message::UpdateTargetLength(s) => {
+                self.target_length = s;
+                Task::none()
+            }
+            Message::UpdateMinLength(s) => {
+                self.min_length = s;
+                Task::none()
+            }
+            Message::UpdateMaxLength(s) => {
+                self.max_length = s;
+                Task::none()
+            }
+            Message::ClickSplitPoint(split_point) => {
+                if let Some(audio) = self.audio.as_mut() {
+                    audio.toggle_selected_split_points(split_point);
+                }
+                Task::none()
+            }
+            Message::PreviewSplitPoint(split_point) => {
+                if self.audio.is_none() {
+                    self.set_warning(warning::NO_AUDIO_LOADED, DebugId::WarningNoAudioLoaded);
+                } else if let Some(roll) = self.apply_result(
+                    self.preview_duration
+                        .parse()
+                        .map_err(|e: ParseFloatError| e.into()),
+                ) && self.check_duration(roll)
+                    && let Some(audio) = self.audio.as_mut()
+                {
+                    let roll = Duration::from_secs_f32(roll);
+                    let (return_to, was_playing) = match self.preview.take() {
+                        Some(preview) => preview.origin(),
+                        None => (audio.get_pos(), self.is_playing),
+                    };
+                    let mut preview = Preview::new(
+                        split_point,
+                        roll,
+                        return_to,
+                        was_playing,
+                        self.preview_gap.then_some(preview::GAP),
+                    );
+                    if let Some(last) = audio.playback_end() {
+                        preview.clamp_end(last.saturating_sub(END_MARGIN));
+                    }
+                    audio.seek(Preview::start(split_point, roll));
+                    audio.set_play();
+                    self.is_playing = true;
+                    self.preview = Some(preview);
+                    self.set_info(
+                        info::PREVIEW_CUT
+                            .replace("{}", &format!("{:.2}", split_point.as_secs_f32())),
+                        DebugId::InfoPreviewCut,
+                    );
+                }
+                Task::none()
+            }
+            Message::UpdatePreviewDuration(s) => {
+                self.preview_duration = s;
+                Task::none()
+            }
+            Message::TogglePreviewGap(gap) => {
+                self.preview_gap = gap;
+                Task::none()
+            }
+            Message::ToggleSpanLoop(id) => {
+                // playback skips excluded spans, a loop over one would fight the skip
+                if self
+                    .audio
+                    .as_ref()
+                    .and_then(|a| a.spans().iter().find(|s| s.id() == id))
+                    .is_some_and(AudioSpan::is_excluded)
+                {
+                    return Task::none();
+                }
+                if self.looping.and_then(|l| l.span()) == Some(id) {
+                    self.looping = None;
+                } else if let Some(audio) = self.audio.as_mut() {
+                    self.looping = Some(Looping::Span(id));
+                    if let Some((start, _)) = self.looping.and_then(|l| l.range(audio)) {
+                        audio.seek(start);
+                    }
+                }
+                Task::none()
+            }
+            Message::SetLoopStart => {
+                if let Some(audio) = &self.audio {
+                    let pos = audio.get_pos();
+                    self.loop_start = Some(pos);
+                    self.set_info(
+                        info::LOOP_START_SET.replace("{}", &format!("{:.2}", pos.as_secs_f32())),
+                        DebugId::InfoLoopStartSet,
+                    );
+                } else {
+                    self.set_warning(warning::NO_AUDIO_LOADED, DebugId::WarningNoAudioLoaded);
+                }
+                Task::none()
+            }
+            Message::SetLoopEnd => {
+                if let Some(audio) = self.audio.as_mut() {
+                    let pos = audio.get_pos();
+                    if let Some(start) = self.loop_start
+                        && start < pos
+                    {
+                        self.looping = Some(Looping::Range(start, pos));
+                        audio.seek(start);
+                        self.set_info(
+                            info::LOOP_RANGE_SET
+                                .replacen("{}", &format!("{:.2}", start.as_secs_f32()), 1)
+                                .replacen("{}", &format!("{:.2}", pos.as_secs_f32()), 1),
+                            DebugId::InfoLoopRangeSet,
+                        );
+                    } else {
+                        self.set_warning(
+                            warning::INVALID_LOOP_RANGE,
+                            DebugId::WarningInvalidLoopRange,
+                        );
+                    }
+                } else {
+                    self.set_warning(warning::NO_AUDIO_LOADED, DebugId::WarningNoAudioLoaded);
+                }
+                Task::none()
+            }
+            Message::ClearLoop => {
+                self.looping = None;
+                self.loop_start = None;
+                Task::none()
+            }
+            Message::KeyPressed(key, modifiers) => {
+                if self.show_help && key == Key::Named(Named::Escape) {
+                    self.show_help = false;
+                    Task::none()
+                } else if let Some(action) = self.key_bindings.action(&key, modifiers) {
+                    self.update(Message::Shortcut(action))
+                } else {
+                    Task::none()
+                }
+            }
+            Message::Shortcut(action) => self.apply_shortcut(action),
+            Message::KeyBindingsLoaded(key_bindings) => {
+                self.apply_result_and(key_bindings, |this, key_bindings| {
+                    this.key_bindings = key_bindings
+                });
+                Task::none()
+            }
+            Message::ToggleHelp => {
+                self.show_help = !self.show_help;
+                Task::none()
+            }
+            Message::SelectPreset(preset) => {
+                self.threshold = preset.threshold().to_string();
+                self.duration = preset.duration().to_string();
+                self.save_settings()
+            }
+            Message::SettingsLoaded(settings) => {
+                self.apply_result_and(settings, |this, settings| {
+                    this.import_folder = settings.import_folder;
+                    this.export_path = settings.export_folder;
+                    this.threshold = settings.threshold;
+                    this.duration = settings.duration;
+                    this.preview_duration = settings.preview_duration;
+                    this.preview_gap = settings.preview_gap;
+                    this.trim_silence = settings.trim_silence;
+                    this.trim_padding = settings.trim_padding;
+                    this.fade_in = settings.fade_in;
+                    this.fade_out = settings.fade_out;
+                    this.fade_curve = settings.fade_curve;
+                    this.snap_to_zero_crossing = settings.snap_to_zero_crossing;
+                    this.normalize = settings.normalize;
+                    this.loudness_target = settings.loudness_target;
+                    this.true_peak = settings.true_peak;
+                    this.normalize_mode = settings.normalize_mode;
+                    this.min_segment_length = settings.min_segment_length;
+                    this.max_segments = settings.max_segments;
+                    this.split_mode = settings.split_mode;
+                    this.interval = settings.interval;
+                    this.parts = settings.parts;
+                    this.snap_window = settings.snap_window;
+                    this.min_score = settings.min_score;
+                    this.target_length_mode = settings.target_length_mode;
+                    this.target_length = settings.target_length;
+                    this.min_length = settings.min_length;
+                    this.max_length = settings.max_length;
+                    this.marker_tone = settings.marker_tone;
+                    this.remove_markers = settings.remove_markers;
+                    this.presets = settings.presets;
+                    // an unreadable settings file must not be overwritten with the defaults
+                    this.persist_settings = true;
+                });
+                Task::none()
+            }
+            Message::SettingsSaved(r) => {
+                self.apply_result(r);
+                Task::none()
+            }
+            Message::ToggleTrimSilence(trim) => {
+                self.trim_silence = trim;
+                Task::none()
+            }
+            Message::UpdateTrimPadding(s) => {
+                self.trim_padding = s;
+                Task::none()
+            }
+            Message::UpdateFadeIn(s) => {
+                self.fade_in = s;
+                Task::none()
+            }
+            Message::UpdateFadeOut(s) => {
+                self.fade_out = s;
+                Task::none()
+            }
+            Message::SelectFadeCurve(curve) => {
+                self.fade_curve = curve;
+                Task::none()
+            }
+            Message::ToggleSnapToZeroCrossing(snap) => {
+                self.snap_to_zero_crossing = snap;
+                Task::none()
+            }
+            Message::ToggleNormalize(normalize) => {
+                self.normalize = normalize;
+                Task::none()
+            }
+            Message::UpdateLoudnessTarget(s) => {
+                self.loudness_target = s;
+                Task::none()
+            }
+            Message::UpdateTruePeak(s) => {
+                self.true_peak = s;
+                Task::none()
+            }
+            Message::SelectNormalizeMode(mode) => {
+                self.normalize_mode = mode;
+                Task::none()
+            }
+            Message::UpdateDuration(s) => {
+                self.duration = s;
+                self.preview_live_analysis();
+                Task::none()
+            }
+            Message::UpdateMinSegmentLength(s) => {
+                self.min_segment_length = s;
+                Task::none()
+            }
+            Message::UpdateMaxSegments(s) => {
+                self.max_segments = s;
+                Task::none()
+            }
+            Message::UpdateThreshold(s) => {
+                self.threshold = s;
+                let threshold = self.threshold.parse().ok();
+                if let Some(audio) = self.audio.as_mut() {
+                    audio.set_threshold(threshold);
+                }
+                self.preview_live_analysis();
+                Task::none()
+            }
+            Message::EnvelopeLoaded(envelope) => {
+                self.apply_result_and(envelope, |this, envelope| {
+                    let Some(audio) = this.audio.as_mut() else {
+                        return;
+                    };
+                    audio.set_envelope(envelope);
+                    // an analysis that finished first gets its depths now
+                    let silences = audio.silences().to_vec();
+                    if !silences.is_empty() {
+                        let silences = this.with_depths(silences);
+                        if let Some(audio) = this.audio.as_mut() {
+                            audio.set_scores(
+                                silences.iter().map(|s| (s.midpoint(), s.score())).collect(),
+                            );
+                            audio.set_silences(silences);
+                        }
+                    }
+                });
+                Task::none()
+            }
+            Message::SlideThreshold(threshold) => {
+                self.threshold = format!("{threshold:.1}");
+                if let Some(audio) = self.audio.as_mut() {
+                    audio.set_threshold(Some(threshold));
+                }
+                self.preview_live_analysis();
+                Task::none()
+            }
+            Message::SlideDuration(duration) => {
+                self.duration = format!("{duration:.2}");
+                self.preview_live_analysis();
+                Task::none()
+            }
+            Message::ApplyLiveAnalysis => {
+                let silences = self.live_silences();
+                let mut tasks = vec![
+                    self.update(Message::UpdateThreshold(self.threshold.clone())),
+                    self.update(Message::UpdateDuration(self.duration.clone())),
+                ];
+                // the other modes keep their generated split points
+                if self.split_mode == SplitMode::Silence
+                    && let Some(silences) = silences
+                {
+                    tasks.push(self.update(Message::Analyzed(Ok(silences))));
+                }
+                tasks.push(self.save_settings());
+                Task::batch(tasks)
+            }
+            Message::EstimateThreshold => {
+                if let Some(audio) = &self.audio
+                    && !audio.envelope().is_empty()
+                {
+                    let noise_floor = estimate_noise_floor(audio.envelope().levels());
+                    self.update(Message::ThresholdEstimated(Ok(noise_floor)))
+                } else if let Some(path) = self.import_path.clone() {
+                    Task::perform(
+                        async move { Ok(estimate_noise_floor(level_envelope(path).await?.levels())) },
+                        Message::ThresholdEstimated,
+                    )
+                } else {
+                    self.set_warning(warning::NO_AUDIO_LOADED, DebugId::WarningNoAudioLoaded);
+                    Task::none()
+                }
+            }
+            Message::ThresholdEstimated(noise_floor) => match noise_floor {
+                Ok(Some(noise_floor)) => {
+                    // goes through the undo stack like a typed threshold
+                    let task = self.update(Message::UpdateThreshold(format!(
+                        "{:.1}",
+                        noise_floor.threshold
+                    )));
+                    self.set_info(
+                        info::THRESHOLD_ESTIMATED
+                            .replacen("{}", &format!("{:.1}", noise_floor.floor), 1)
+                            .replacen("{}", &format!("{:.1}", noise_floor.threshold), 1),
+                        DebugId::InfoThresholdEstimated,
+                    );
+                    task
+                }
+                Ok(None) => {
+                    self.set_warning(warning::NO_NOISE_FLOOR, DebugId::WarningNoNoiseFloor);
+                    Task::none()
+                }
+                Err(e) => {
+                    self.apply_result::<()>(Err(e));
+                    Task::none()
+                }
+            },
+        }
+    }
+    fn add_to_undo_stack(&mut self, message: &Message) {
+        match message {
+            Message::AudioLoaded(..) => {
+                self.undo_stack.clear();
+                self.redo_stack.clear();
+            }
+
+            // the replay of `Analyzed` derives the split points from these inputs
+            x @ Message::UpdateDuration(..)
+            | x @ Message::UpdateMinSegmentLength(..)
+            | x @ Message::UpdateMaxSegments(..)
+            | x @ Message::UpdateThreshold(..)
+            | x @ Message::SelectSplitMode(..)
+            | x @ Message::UpdateInterval(..)
+            | x @ Message::UpdateSnapWindow(..)
+            | x @ Message::ToggleTargetLengthMode(..)
+            | x @ Message::UpdateTargetLength(..)
+            | x @ Message::UpdateMinLength(..)
+            | x @ Message::UpdateMaxLength(..)
+            | x @ Message::SelectPreset(..)
+            | x @ Message::Analyzed(..)
+            | x @ Message::RangeAnalyzed(..)
+            | x @ Message::SplitPointsGenerated(..)
+            | x @ Message::TrackBoundariesDetected(..)
+            | x @ Message::DeleteAudioSpan(..)
+            | x @ Message::RestoreAudioSpan(..)
+            | x @ Message::TrimSpan(..)
+            | x @ Message::MergeWithNext(..)
+            | x @ Message::MergeWithPrevious(..)
+            | x @ Message::MergeSpans(..)
+            | x @ Message::SpanTextUpdate(..)
+            | x @ Message::ClickSplitPoint(..)
+            | x @ Message::SetSelectedSplitPoints(..)
+            | x @ Message::Split => {
+                self.undo_stack.push(x.clone());
+                self.redo_stack.clear();
+            }
+            _ => {}
+        }
+    }
+    fn apply_shortcut(&mut self, action: Action) -> Task<Message> {
+        match action {
+            Action::Help => return self.update(Message::ToggleHelp),
+            Action::Undo => return self.update(Message::Undo),
+            Action::Redo => return self.update(Message::Redo),
+            Action::Split => return self.update(Message::Split),
+            Action::PlayPause if self.is_playing => return self.update(Message::Pause),
+            Action::PlayPause => return self.update(Message::Play),
+            _ => {}
+        }
+        let Some(audio) = self.audio.as_mut() else {
+            self.set_warning(warning::NO_AUDIO_LOADED, DebugId::WarningNoAudioLoaded);
+            return Task::none();
+        };
+        let pos = audio.get_pos();
+        match action {
+            Action::SeekForward => {
+                // deleted spans at the end are not played
+                let end = audio
+                    .playback_end()
+                    .unwrap_or(audio.duration())
+                    .saturating_sub(END_MARGIN);
+                audio.seek((pos + SEEK_STEP).min(end));
+            }
+            Action::SeekBackward => audio.seek(pos.saturating_sub(SEEK_STEP)),
+            Action::NextSplitPoint => {
+                if let Some(next) = audio.all_split_points().into_iter().find(|p| *p > pos) {
+                    audio.seek(next);
+                }
+            }
+            Action::PreviousSplitPoint => {
+                if let Some(previous) = audio
+                    .all_split_points()
+                    .into_iter()
+                    .rev()
+                    .find(|p| *p + JUMP_BACK_TOLERANCE < pos)
+                {
+                    audio.seek(previous);
+                }
+            }
+            Action::ToggleSplitPoint => {
+                let nearest = audio
+                    .all_split_points()
+                    .into_iter()
+                    .min_by_key(|p| p.abs_diff(pos))
+                    .filter(|p| p.abs_diff(pos) <= PLAYHEAD_TOLERANCE);
+                return match nearest {
+                    Some(split_point) => self.update(Message::ClickSplitPoint(split_point)),
+                    None => {
+                        self.set_warning(
+                            warning::NO_SPLIT_POINT_AT_PLAYHEAD,
+                            DebugId::WarningNoSplitPointAtPlayhead,
+                        );
+                        Task::none()
+                    }
+                };
+            }
+            Action::DeleteSpan => {
+                if let Some(id) = audio.span_at(pos).map(|s| s.id()) {
+                    return self.update(Message::DeleteAudioSpan(id));
+                }
+            }
+            Action::FocusSpanName => {
+                if let Some(id) = audio.span_at(pos).map(|s| s.id()) {
+                    return widget::operation::focus(DebugId::TextInputSpanName(id));
+                }
+            }
+            Action::Help | Action::Undo | Action::Redo | Action::Split | Action::PlayPause => {}
+        }
+        Task::none()
+    }
+    pub fn view(&self) -> Element<'_, Message> {
+        let content = widget::column![
+            self.view_top(),
+            self.view_analysis(),
+            self.view_selection(),
+            self.view_playback(),
+            self.view_export(),
+            self.view_center(),
+            self.view_info()
+        ];
+        if self.show_help {
+            widget::stack![content, self.view_help()].into()
+        } else {
+            content.into()
+        }
+    }
+    fn view_help(&self) -> Element<'_, Message> {
+        let mut column = widget::column![widget::text("Keyboard shortcuts").size(20)].spacing(5);
+        for (binding, action) in self.key_bindings.bindings() {
+            column = column.push(widget::row![
+                widget::text(binding.to_string()).width(150),
+                widget::text(action.description()),
+            ]);
+        }
+        column = column.push(
+            widget::container(widget::button("close").on_press(Message::ToggleHelp))
+                .id(DebugId::ButtonCloseHelp),
+        );
+        widget::opaque(
+            widget::container(
+                widget::container(column)
+                    .padding(10)
+                    .style(widget::container::rounded_box),
+            )
+            .id(DebugId::HelpOverlay)
+            .center(Length::Fill),
+        )
+    }
+    fn view_top(&self) -> Element<'_, Message> {
+        widget::row![
+            widget::container(
+                widget::button("open audio file").on_press(Message::OpenAudioFileDialog)
+            )
+            .id(DebugId::ButtonOpen),
+            if self.is_playing {
+                widget::container(widget::button("pause").on_press(Message::Pause))
+                    .id(DebugId::ButtonPause)
+            } else {
+                widget::container(widget::button("play").on_press(Message::Play))
+                    .id(DebugId::ButtonPlay)
+            },
+            widget::container(
+                widget::pick_list(
+                    self.presets.as_slice(),
+                    self.presets
+                        .iter()
+                        .find(|p| p.threshold() == self.threshold && p.duration() == self.duration),
+                    Message::SelectPreset,
+                )
+                .placeholder("preset")
+            )
+            .id(DebugId::PickListPreset),
+            widget::text("threshold in dB:"),
+            widget::text_input("", &self.threshold)
+                .on_input(Message::UpdateThreshold)
+                .width(INPUT_WIDTH)
+                .id(DebugId::TextInputThreshold),
+            widget::slider(
+                THRESHOLD_SLIDER,
+                self.threshold.parse().unwrap_or(*THRESHOLD_SLIDER.start()),
+                Message::SlideThreshold
+            )
+            .step(0.5)
+            .on_release(Message::ApplyLiveAnalysis)
+            .width(SLIDER_WIDTH),
+            widget::container(widget::button("auto").on_press(Message::EstimateThreshold))
+                .id(DebugId::ButtonAutoThreshold),
+            widget::text("duration in sec:"),
+            widget::text_input("", &self.duration)
+                .on_input(Message::UpdateDuration)
+                .width(INPUT_WIDTH)
+                .id(DebugId::TextInputDuration),
+            widget::slider(
+                DURATION_SLIDER,
+                self.duration.parse().unwrap_or(*DURATION_SLIDER.start()),
+                Message::SlideDuration
+            )
+            .step(0.05)
+            .on_release(Message::ApplyLiveAnalysis)
+            .width(SLIDER_WIDTH),
+            widget::text("min segment in sec:"),
+            widget::text_input("off", &self.min_segment_length)
+                .on_input(Message::UpdateMinSegmentLength)
+                .width(INPUT_WIDTH)
+                .id(DebugId::TextInputMinSegmentLength),
+            widget::text("max segments:"),
+            widget::text_input("off", &self.max_segments)
+                .on_input(Message::UpdateMaxSegments)
+                .width(INPUT_WIDTH)
+                .id(DebugId::TextInputMaxSegments),
+            widget::container(widget::button("undo").on_press(Message::Undo))
+                .id(DebugId::ButtonUndo),
+            widget::container(widget::button("redo").on_press(Message::Redo))
+                .id(DebugId::ButtonRedo),
+            widget::container(widget::button("analyze").on_press(Message::Analyze))
+                .id(DebugId::ButtonAnalyze),
+            widget::container(widget::button("levels").on_press(Message::AnalyzeLevels))
+                .id(DebugId::ButtonAnalyzeLevels),
+            widget::container(widget::button("split").on_press(Message::Split))
+                .id(DebugId::ButtonSplit),
+            widget::container(
+                widget::button("merge selected")
+                    .on_press(Message::MergeSpans(self.selected_spans.clone()))
+            )
+            .id(DebugId::ButtonMergeSelected),
+            widget::container(widget::button("export").on_press(Message::OpenExportDialog))
+                .id(DebugId::ButtonExport),
+        ]
+        .spacing(5)
+        .align_y(Vertical::Center)
+        .wrap()
+        .into()
+    }
+    fn view_playback(&self) -> Element<'_, Message> {
+        widget::row![
+            widget::text("preview in sec:"),
+            widget::text_input("", &self.preview_duration)
+                .on_input(Message::UpdatePreviewDuration)
+                .width(INPUT_WIDTH)
+                .id(DebugId::TextInputPreview),
+            widget::checkbox(self.preview_gap)
+                .label("gap at cut")
+                .on_toggle(Message::TogglePreviewGap),
+            widget::container(widget::button("A").on_press(Message::SetLoopStart))
+                .id(DebugId::ButtonLoopStart),
+            widget::container(widget::button("B").on_press(Message::SetLoopEnd))
+                .id(DebugId::ButtonLoopEnd),
+            widget::container(widget::button("clear loop").on_press(Message::ClearLoop))
+                .id(DebugId::ButtonLoopClear),
+            widget::container(widget::button("shortcuts").on_press(Message::ToggleHelp))
+                .id(DebugId::ButtonHelp),
+        ]
+        .spacing(5)
+        .align_y(Vertical::Center)
+        .wrap()
+        .into()
+    }
+    fn view_analysis(&self) -> Element<'_, Message> {
+        widget::row![
+            widget::container(widget::pick_list(
+                &SplitMode::ALL[..],
+                Some(self.split_mode),
+                Message::SelectSplitMode,
+            ))
+            .id(DebugId::PickListSplitMode),
+            widget::text("interval in sec:"),
+            widget::text_input("", &self.interval)
+                .on_input(Message::UpdateInterval)
+                .width(INPUT_WIDTH)
+                .id(DebugId::TextInputInterval),
+            widget::text("parts:"),
+            widget::text_input("", &self.parts)
+                .on_input(Message::UpdateParts)
+                .width(INPUT_WIDTH)
+                .id(DebugId::TextInputParts),
+            widget::text("snap within ± sec:"),
+            widget::text_input("", &self.snap_window)
+                .on_input(Message::UpdateSnapWindow)
+                .width(INPUT_WIDTH)
+                .id(DebugId::TextInputSnapWindow),
+            widget::checkbox(self.target_length_mode)
+                .label("select cuts for a target length")
+                .on_toggle(Message::ToggleTargetLengthMode),
+            widget::text("target in min:"),
+            widget::text_input("", &self.target_length)
+                .on_input(Message::UpdateTargetLength)
+                .width(INPUT_WIDTH)
+                .id(DebugId::TextInputTargetLength),
+            widget::text("min:"),
+            widget::text_input("", &self.min_length)
+                .on_input(Message::UpdateMinLength)
+                .width(INPUT_WIDTH)
+                .id(DebugId::TextInputMinLength),
+            widget::text("max:"),
+            widget::text_input("", &self.max_length)
+                .on_input(Message::UpdateMaxLength)
+                .width(INPUT_WIDTH)
+                .id(DebugId::TextInputMaxLength),
+            widget::text("marker tone, f.e. 1000 Hz or DTMF digits:"),
+            widget::text_input("", &self.marker_tone)
+                .on_input(Message::UpdateMarkerTone)
+                .width(INPUT_WIDTH)
+                .id(DebugId::TextInputMarkerTone),
+            widget::checkbox(self.remove_markers)
+                .label("remove marker tones")
+                .on_toggle(Message::ToggleRemoveMarkers),
+            widget::container(widget::button("classify").on_press(Message::Classify))
+                .id(DebugId::ButtonClassify),
+            widget::container(
+                widget::button("split at class changes").on_press(Message::SplitAtClassChanges)
+            )
+            .id(DebugId::ButtonSplitAtClassChanges),
+            widget::text("range from sec:"),
+            widget::text_input("", &self.analyze_from)
+                .on_input(Message::UpdateAnalyzeFrom)
+                .width(INPUT_WIDTH)
+                .id(DebugId::TextInputAnalyzeFrom),
+            widget::text("to:"),
+            widget::text_input("", &self.analyze_to)
+                .on_input(Message::UpdateAnalyzeTo)
+                .on_submit(Message::AnalyzeInputRange)
+                .width(INPUT_WIDTH)
+                .id(DebugId::TextInputAnalyzeTo),
+            widget::container(widget::button("analyze range").on_press(Message::AnalyzeInputRange))
+                .id(DebugId::ButtonAnalyzeRange),
+        ]
+        .spacing(5)
+        .align_y(Vertical::Center)
+        .wrap()
+        .into()
+    }
+    fn view_selection(&self) -> Element<'_, Message> {
+        widget::row![
+            widget::container(
+                widget::button("select all").on_press(Message::BulkSelect(Selection::All))
+            )
+            .id(DebugId::ButtonSelectAll),
+            widget::container(
+                widget::button("select none").on_press(Message::BulkSelect(Selection::None))
+            )
+            .id(DebugId::ButtonSelectNone),
+            widget::container(
+                widget::button("invert selection").on_press(Message::BulkSelect(Selection::Invert))
+            )
+            .id(DebugId::ButtonInvertSelection),
+            widget::text("every:"),
+            widget::text_input("", &self.nth)
+                .on_input(Message::UpdateNth)
+                .on_submit(Message::SelectEveryNth)
+                .width(INPUT_WIDTH)
+                .id(DebugId::TextInputNth),
+            widget::container(widget::button("select every nth").on_press(Message::SelectEveryNth))
+                .id(DebugId::ButtonSelectEveryNth),
+            widget::text("min score in %:"),
+            widget::text_input("", &self.min_score)
+                .on_input(Message::UpdateMinScore)
+                .on_submit(Message::SelectAboveScore)
+                .width(INPUT_WIDTH)
+                .id(DebugId::TextInputMinScore),
+            widget::container(
+                widget::button("select above score").on_press(Message::SelectAboveScore)
+            )
+            .id(DebugId::ButtonSelectAboveScore),
+        ]
+        .spacing(5)
+        .align_y(Vertical::Center)
+        .wrap()
+        .into()
+    }
+    fn view_export(&self) -> Element<'_, Message> {
+        widget::row![
+            widget::checkbox(self.trim_silence)
+                .label("trim silence on export")
+                .on_toggle(Message::ToggleTrimSilence),
+            widget::text("keep in sec:"),
+            widget::text_input("", &self.trim_padding)
+                .on_input(Message::UpdateTrimPadding)
+                .width(INPUT_WIDTH)
+                .id(DebugId::TextInputTrimPadding),
+            widget::text("fade in:"),
+            widget::text_input("", &self.fade_in)
+                .on_input(Message::UpdateFadeIn)
+                .width(INPUT_WIDTH)
+                .id(DebugId::TextInputFadeIn),
+            widget::text("fade out:"),
+            widget::text_input("", &self.fade_out)
+                .on_input(Message::UpdateFadeOut)
+                .width(INPUT_WIDTH)
+                .id(DebugId::TextInputFadeOut),
+            widget::container(widget::pick_list(
+                &FadeCurve::ALL[..],
+                Some(self.fade_curve),
+                Message::SelectFadeCurve,
+            ))
+            .id(DebugId::PickListFadeCurve),
+            widget::checkbox(self.snap_to_zero_crossing)
+                .label("snap cuts to zero crossings")
+                .on_toggle(Message::ToggleSnapToZeroCrossing),
+            widget::checkbox(self.normalize)
+                .label("normalize loudness")
+                .on_toggle(Message::ToggleNormalize),
+            widget::text("LUFS:"),
+            widget::text_input("", &self.loudness_target)
+                .on_input(Message::UpdateLoudnessTarget)
+                .width(INPUT_WIDTH)
+                .id(DebugId::TextInputLoudnessTarget),
+            widget::text("true peak dBTP:"),
+            widget::text_input("", &self.true_peak)
+                .on_input(Message::UpdateTruePeak)
+                .width(INPUT_WIDTH)
+                .id(DebugId::TextInputTruePeak),
+            widget::container(widget::pick_list(
+                &NormalizeMode::ALL[..],
+                Some(self.normalize_mode),
+                Message::SelectNormalizeMode,
+            ))
+            .id(DebugId::PickListNormalizeMode),
+        ]
+        .spacing(5)
+        .align_y(Vertical::Center)
+        .wrap()
+        .into()
+    }
+    fn view_center(&self) -> Element<'_, Message> {
+        if let Some(audio) = &self.audio {
+            widget::container(widget::column![
+                widget::space().height(Length::FillPortion(2)),
+                audio.view(self.looping.and_then(|l| l.span()), &self.selected_spans),
+                self.view_defects(audio.defects()),
+                widget::space().height(Length::FillPortion(3)),
+            ])
+        } else {
+            widget::container(widget::text("Please open an audio file"))
+        }
+        .center(Length::Fill)
+        .into()
+    }
+    fn view_defects<'a>(&self, defects: &'a [Defect]) -> Element<'a, Message> {
+        if defects.is_empty() {
+            return widget::space().into();
+        }
+        let mut row = widget::row![widget::text(format!("{} defect(s):", defects.len()))]
+            .spacing(5)
+            .align_y(Vertical::Center);
+        for (index, defect) in defects.iter().enumerate() {
+            row = row.push(
+                widget::container(
+                    widget::button(widget::text(format!(
+                        "{} {:.2} s",
+                        defect.kind(),
+                        defect.start().as_secs_f32()
+                    )))
+                    .style(match defect.kind() {
+                        DefectKind::Clipping => widget::button::danger,
+                        DefectKind::Dropout => widget::button::warning,
+                    })
+                    .on_press(Message::JumpToDefect(defect.start())),
+                )
+                .id(DebugId::ButtonDefect(index)),
+            );
+        }
+        widget::scrollable(row.padding(5))
+            .horizontal()
+            .width(Length::Fill)
+            .into()
+    }
+    fn view_info(&self) -> Element<'_, Message> {
+        let info = match &self.info {
+            UserInfo::None => widget::container(widget::space()),
+            UserInfo::Info(text, id) => {
+                widget::container(widget::text(text).style(widget::text::base)).id(*id)
+            }
+            UserInfo::Waring(text, id) => {
+                widget::container(widget::text(text).style(widget::text::warning)).id(*id)
+            }
+            UserInfo::Error(e) => {
+                widget::container(widget::text(format!("{e}")).style(widget::text::danger))
+                    .id(e.id())
+            }
+        }
+        .padding(5);
+        match &self.analysis {
+            Some(analysis) => widget::row![
+                widget::text(analysis.to_string()),
+                widget::container(
+                    widget::button("cancel")
+                        .style(widget::button::danger)
+                        .on_press(Message::CancelAnalysis)
+                )
+                .id(DebugId::ButtonCancelAnalysis),
+                info,
+            ]
+            .spacing(5)
+            .padding(5)
+            .align_y(Vertical::Center)
+            .into(),
+            None => info.into(),
+        }
+    }
+    pub fn subscription(&self) -> Subscription<Message> {
+        let tick = iced::time::every(Duration::from_millis(16)).map(|_| Message::Tick);
+        Subscription::batch([
+            tick,
+            iced::window::events().map(|f| Message::WindowEvent(f.1)),
+            keyboard::listen().filter_map(|e| match e {
+                keyboard::Event::KeyPressed { key, modifiers, .. } => {
+                    Some(Message::KeyPressed(key, modifiers))
+                }
+                _ => None,
+            }),
+        ])
+    }
+    pub fn set_audio(&mut self, audio: Audio) {
+        self.audio = Some(audio);
+        let threshold = self.threshold.parse().ok();
+        if let Some(audio) = self.audio.as_mut() {
+            audio.set_threshold(threshold);
+        }
+        self.preview = None;
+        self.looping = None;
+        self.loop_start = None;
+        self.selected_spans.clear();
+        self.is_playing = true;
+        self.set_info(info::AUDIO_LOADED, DebugId::InfoAudioLoaded);
+    }
+    fn apply_result<T>(&mut self, value: Result<T, Error>) -> Option<T> {
+        match value {
+            Ok(v) => Some(v),
+            Err(e) => {
+                self.info = UserInfo::Error(e);
+                None
+            }
+        }
+    }
+    fn apply_result_and<T>(&mut self, value: Result<T, Error>, mut then: impl FnMut(&mut Self, T)) {
+        match value {
+            Ok(v) => then(self, v),
             Err(e) => self.info = UserInfo::Error(e),
         }
     }
+    // depths from the cached envelope, `EnvelopeLoaded` adds them when it arrives later
+    fn with_depths(&self, silences: Vec<Silence>) -> Vec<Silence> {
+        match (&self.audio, self.threshold.trim().parse()) {
+            (Some(audio), Ok(threshold)) => measure_depths(audio.envelope(), silences, threshold),
+            _ => silences,
+        }
+    }
+    // `None` until the envelope is cached by a first analysis
+    fn live_silences(&self) -> Option<Vec<Silence>> {
+        let audio = self.audio.as_ref()?;
+        if audio.envelope().is_empty() {
+            return None;
+        }
+        let threshold = self.threshold.parse().ok()?;
+        let duration: f32 = self.duration.parse().ok()?;
+        Some(envelope_silences(
+            audio.envelope(),
+            threshold,
+            Duration::from_secs_f32(duration.max(0.0)),
+        ))
+    }
+    // shows the split points while a slider is dragged or a valid value is typed,
+    // `ApplyLiveAnalysis` records them
+    fn preview_live_analysis(&mut self) {
+        if self.split_mode != SplitMode::Silence {
+            return;
+        }
+        let constraints = self.constraints().unwrap_or_default();
+        if let Some(silences) = self.live_silences()
+            && let Some(audio) = self.audio.as_mut()
+        {
+            let silences = constraints.apply(silences, audio.duration());
+            Audio::set_split_points(
+                audio.spans_mut(),
+                silences.iter().map(Silence::midpoint).collect(),
+            );
+            audio.set_scores(silences.iter().map(|s| (s.midpoint(), s.score())).collect());
+        }
+    }
+    // a running analysis is cancelled, its split points are shown while they are found;
+    // the envelope is decoded once per file next to the first analysis
+    fn start_analysis(
+        &mut self,
+        path: PathBuf,
+        threshold: f32,
+        min_silence_duration: Duration,
+        range: Option<(Duration, Duration)>,
+    ) -> Task<Message> {
+        if self.analysis.is_some() {
+            let _ = self.update(Message::CancelAnalysis);
+        }
+        let end = match range {
+            Some((_, end)) => end,
+            None => self.audio.as_ref().map(Audio::duration).unwrap_or_default(),
+        };
+        let envelope = match &self.audio {
+            Some(audio) if audio.envelope().is_empty() => {
+                Task::perform(level_envelope(path.clone()), |envelope| {
+                    Message::EnvelopeLoaded(envelope.map(Arc::new))
+                })
+            }
+            _ => Task::none(),
+        };
+        let (task, handle) = Task::run(
+            analyze_silence(path, threshold, min_silence_duration, range),
+            Message::AnalysisProgress,
+        )
+        .abortable();
+        self.analysis = Some(Analysis::new(handle, range, end));
+        Task::batch([task, envelope])
+    }
     fn check_duration(&mut self, duration: f32) -> bool {
         if duration.is_sign_negative() {
             self.apply_result::<()>(Err(Error::new(
@@ -375,6 +1956,198 @@ impl<P: AudioPlayer> AudioSplit<P> {
             true
         }
     }
+    fn export_options(&mut self) -> Option<ExportOptions> {
+        let mut options = ExportOptions::default();
+        if self.trim_silence {
+            let padding = self.apply_result(
+                self.trim_padding
+                    .parse()
+                    .map_err(|e: ParseFloatError| e.into()),
+            )?;
+            if !self.check_duration(padding) {
+                return None;
+            }
+            options.trim_silence = Some(Duration::from_secs_f32(padding));
+        }
+        options.fade_in = self.parse_secs(self.fade_in.clone())?;
+        options.fade_out = self.parse_secs(self.fade_out.clone())?;
+        options.fade_curve = self.fade_curve;
+        options.snap_to_zero_crossing = self.snap_to_zero_crossing;
+        if self.normalize {
+            options.normalize = Some(Normalize {
+                target: self.parse_level(self.loudness_target.clone())?,
+                true_peak: self.parse_level(self.true_peak.clone())?,
+                mode: self.normalize_mode,
+            });
+        }
+        Some(options)
+    }
+    fn generated_split_points(&mut self) -> Option<Vec<Duration>> {
+        let Some(duration) = self.audio.as_ref().map(Audio::duration) else {
+            self.set_warning(warning::NO_AUDIO_LOADED, DebugId::WarningNoAudioLoaded);
+            return None;
+        };
+        match self.split_mode {
+            SplitMode::EqualParts => {
+                let parts = self.apply_result(
+                    self.parts
+                        .trim()
+                        .parse()
+                        .map_err(|e: ParseIntError| e.into()),
+                )?;
+                Some(equal_parts(duration, parts))
+            }
+            _ => Some(interval_points(
+                duration,
+                self.parse_secs(self.interval.clone())?,
+            )),
+        }
+    }
+    fn snap_interval(&mut self) -> Option<(Duration, Duration)> {
+        let interval = self.parse_secs(self.interval.clone())?;
+        let window = self.parse_secs(self.snap_window.clone())?;
+        Some((interval, window))
+    }
+    // empty inputs switch a constraint off
+    fn constraints(&mut self) -> Option<Constraints> {
+        let min_segment_length = match self.min_segment_length.trim() {
+            "" => None,
+            _ => Some(self.parse_secs(self.min_segment_length.clone())?),
+        };
+        let max_segments = match self.max_segments.trim() {
+            "" => None,
+            max => Some(self.apply_result(max.parse().map_err(|e: ParseIntError| e.into()))?),
+        };
+        Some(Constraints {
+            min_segment_length,
+            max_segments,
+        })
+    }
+    // the inputs are in minutes, min and max fall back to the target
+    fn segment_length(&mut self) -> Option<SegmentLength> {
+        let minutes = |d: Duration| d * 60;
+        let target = minutes(self.parse_secs(self.target_length.clone())?);
+        let min = match self.min_length.trim() {
+            "" => target,
+            _ => minutes(self.parse_secs(self.min_length.clone())?),
+        };
+        let max = match self.max_length.trim() {
+            "" => target,
+            _ => minutes(self.parse_secs(self.max_length.clone())?),
+        };
+        if min.is_zero() || min > target || target > max {
+            self.set_warning(
+                warning::INVALID_SEGMENT_LENGTH,
+                DebugId::WarningInvalidSegmentLength,
+            );
+            return None;
+        }
+        Some(SegmentLength { target, min, max })
+    }
+    // levels in dB may be negative
+    fn parse_level(&mut self, input: String) -> Option<f32> {
+        self.apply_result(input.trim().parse().map_err(|e: ParseFloatError| e.into()))
+    }
+    fn parse_percent(&mut self, input: String) -> Option<f32> {
+        let percent = self.parse_level(input)?;
+        if (0.0..=100.0).contains(&percent) {
+            Some(percent)
+        } else {
+            self.apply_result(Err(Error::new(
+                error::ErrorKind::PercentOutOfRange,
+                DebugId::ErrorPercentOutOfRange,
+            )))
+        }
+    }
+    fn report_gains(&mut self, gains: &[SegmentGain]) {
+        let (Some(min), Some(max)) = (
+            gains.iter().map(|g| g.gain).reduce(f32::min),
+            gains.iter().map(|g| g.gain).reduce(f32::max),
+        ) else {
+            return;
+        };
+        self.set_info(
+            info::LOUDNESS_NORMALIZED
+                .replacen("{}", &gains.len().to_string(), 1)
+                .replacen("{}", &format!("{min:.1}"), 1)
+                .replacen("{}", &format!("{max:.1}"), 1)
+                .replacen("{}", LOUDNESS_REPORT, 1),
+            DebugId::InfoLoudnessNormalized,
+        );
+    }
+    // an empty field counts as zero
+    fn parse_secs(&mut self, input: String) -> Option<Duration> {
+        if input.trim().is_empty() {
+            return Some(Duration::ZERO);
+        }
+        let secs =
+            self.apply_result(input.trim().parse().map_err(|e: ParseFloatError| e.into()))?;
+        self.check_duration(secs)
+            .then(|| Duration::from_secs_f32(secs))
+    }
+    fn report_merge(&mut self, merged: Option<usize>) {
+        match merged {
+            None => self.set_warning(warning::NO_AUDIO_LOADED, DebugId::WarningNoAudioLoaded),
+            Some(0) => {
+                self.set_warning(warning::NO_ADJACENT_SPANS, DebugId::WarningNoAdjacentSpans)
+            }
+            Some(merged) => self.set_info(
+                info::SPANS_MERGED.replace("{}", &merged.to_string()),
+                DebugId::InfoSpansMerged(merged),
+            ),
+        }
+    }
+    fn load_audio_file(&mut self, path: PathBuf) -> Task<Message> {
+        self.import_folder = path.parent().map(|p| p.to_path_buf());
+        self.extension = path.extension().map(|s| s.into());
+        self.import_path = Some(path.clone());
+        Task::batch([
+            Task::perform(
+                open_audio_file(path, self.audio_player.get_player()),
+                Message::AudioLoaded,
+            ),
+            self.save_settings(),
+        ])
+    }
+    // settings are only written once they were loaded at startup, tests never touch the file
+    fn save_settings(&self) -> Task<Message> {
+        if !self.persist_settings {
+            return Task::none();
+        }
+        let settings = Settings {
+            import_folder: self.import_folder.clone(),
+            export_folder: self.export_path.clone(),
+            threshold: self.threshold.clone(),
+            duration: self.duration.clone(),
+            preview_duration: self.preview_duration.clone(),
+            preview_gap: self.preview_gap,
+            trim_silence: self.trim_silence,
+            trim_padding: self.trim_padding.clone(),
+            fade_in: self.fade_in.clone(),
+            fade_out: self.fade_out.clone(),
+            fade_curve: self.fade_curve,
+            snap_to_zero_crossing: self.snap_to_zero_crossing,
+            normalize: self.normalize,
+            loudness_target: self.loudness_target.clone(),
+            true_peak: self.true_peak.clone(),
+            normalize_mode: self.normalize_mode,
+            min_segment_length: self.min_segment_length.clone(),
+            max_segments: self.max_segments.clone(),
+            split_mode: self.split_mode,
+            interval: self.interval.clone(),
+            parts: self.parts.clone(),
+            snap_window: self.snap_window.clone(),
+            min_score: self.min_score.clone(),
+            target_length_mode: self.target_length_mode,
+            target_length: self.target_length.clone(),
+            min_length: self.min_length.clone(),
+            max_length: self.max_length.clone(),
+            marker_tone: self.marker_tone.clone(),
+            remove_markers: self.remove_markers,
+            presets: self.presets.clone(),
+        };
+        Task::perform(save_settings(settings), Message::SettingsSaved)
+    }
     fn set_warning(&mut self, warning: impl Into<String>, id: DebugId) {
         self.info = UserInfo::Waring(warning.into(), id)
     }
@@ -383,10 +2156,12 @@ impl<P: AudioPlayer> AudioSplit<P> {
     }
     pub fn replay_messages(&mut self) {
         let cloned = self.undo_stack.clone();
+        let redo_stack = std::mem::take(&mut self.redo_stack);
         for message in &cloned {
             let _ = self.update(message.clone());
         }
         self.undo_stack = cloned;
+        self.redo_stack = redo_stack;
     }
     pub fn title(&self) -> String {
         match &self.audio {
@@ -409,22 +2184,113 @@ pub enum Message {
     Play,
     Split,
     Undo,
+    Redo,
     DeleteAudioSpan(u32),
+    RestoreAudioSpan(u32),
+    TrimSpan(u32, Duration, Duration),
+    UpdateSpanStartInput(u32, String),
+    UpdateSpanEndInput(u32, String),
+    SubmitSpanRange(u32),
+    MergeWithNext(u32),
+    MergeWithPrevious(u32),
+    MergeSpans(Vec<u32>),
+    ToggleSpanSelection(u32),
     SpanTextUpdate(u32, String),
     WindowEvent(iced::window::Event),
     Analyze,
-    Analyzed(Result<Vec<Duration>, Error>),
+    Analyzed(Result<Vec<Silence>, Error>),
+    AnalysisProgress(AnalysisEvent),
+    EnvelopeLoaded(Result<Arc<Envelope>, Error>),
+    SlideThreshold(f32),
+    SlideDuration(f32),
+    ApplyLiveAnalysis,
+    CancelAnalysis,
+    AnalyzeSpan(u32),
+    UpdateAnalyzeFrom(String),
+    UpdateAnalyzeTo(String),
+    AnalyzeInputRange,
+    AnalyzeRange(Duration, Duration),
+    RangeAnalyzed(Duration, Duration, Result<Vec<Silence>, Error>),
+    DefectsDetected(Result<Vec<Defect>, Error>),
+    JumpToDefect(Duration),
+    Classify,
+    Classified(Result<Vec<ClassRegion>, Error>),
+    SplitAtClassChanges,
+    TrackBoundariesDetected(Result<Vec<(Duration, f32)>, Error>),
+    UpdateMarkerTone(String),
+    ToggleRemoveMarkers(bool),
+    MarkersDetected(Result<Vec<Marker>, Error>),
+    AnalyzeLevels,
+    LevelsMeasured(Result<Vec<(u32, Levels)>, Error>),
     ClickSplitPoint(Duration),
+    SetSelectedSplitPoints(Vec<Duration>),
+    BulkSelect(Selection),
+    UpdateNth(String),
+    SelectEveryNth,
+    UpdateMinScore(String),
+    SelectAboveScore,
+    SplitPointsGenerated(Vec<Duration>),
+    SelectSplitMode(SplitMode),
+    UpdateInterval(String),
+    UpdateParts(String),
+    UpdateSnapWindow(String),
+    ToggleTargetLengthMode(bool),
+    UpdateTargetLength(String),
+    UpdateMinLength(String),
+    UpdateMaxLength(String),
+    PreviewSplitPoint(Duration),
+    UpdatePreviewDuration(String),
+    TogglePreviewGap(bool),
+    ToggleSpanLoop(u32),
+    SetLoopStart,
+    SetLoopEnd,
+    ClearLoop,
+    KeyPressed(Key, Modifiers),
+    Shortcut(Action),
+    KeyBindingsLoaded(Result<KeyBindings, Error>),
+    SelectPreset(Preset),
+    SettingsLoaded(Result<Settings, Error>),
+    SettingsSaved(Result<(), Error>),
+    ToggleTrimSilence(bool),
+    UpdateTrimPadding(String),
+    UpdateFadeIn(String),
+    UpdateFadeOut(String),
+    SelectFadeCurve(FadeCurve),
+    ToggleSnapToZeroCrossing(bool),
+    ToggleNormalize(bool),
+    UpdateLoudnessTarget(String),
+    UpdateTruePeak(String),
+    SelectNormalizeMode(NormalizeMode),
+    ToggleHelp,
     UpdateDuration(String),
+    UpdateMinSegmentLength(String),
+    UpdateMaxSegments(String),
     UpdateThreshold(String),
-    AudioSaved(Result<(), Error>),
+    EstimateThreshold,
+    ThresholdEstimated(Result<Option<NoiseFloor>, Error>),
+    AudioSaved(Result<Vec<SegmentGain>, Error>),
 }
 
 #[cfg(test)]
 mod test {
-    use std::time::Duration;
+    use std::{sync::Arc, time::Duration};
+
+    use iced::keyboard::{Key, Modifiers, key::Named};
 
-    use crate::audio_split::{Audio, AudioSpan};
+    use crate::audio_split::{
+        Audio, AudioSpan,
+        analyze::{
+            self, Constraints, DefectDetector, DefectKind, Envelope, SegmentLength, Silence,
+        },
+        classify::{self, ClassRegion, Features, SoundClass},
+        export::{self, ExportOptions, FadeCurve},
+        loudness::{self, Levels, Loudness, Normalize, NormalizeMode},
+        markers::{self, MarkerTone},
+        selection::Selection,
+        settings::{Preset, Settings},
+        shortcuts::{Action, BindingError, KeyBindings},
+        tracks,
+    };
 
     #[test]
     fn contains_position() {
@@ -454,4 +2320,644 @@ mod test {
         Audio::set_split_points(&mut spans, split_points);
         assert_eq!(spans[0].split_points(), control);
     }
+    #[test]
+    fn merge_spans() {
+        let (player, _queue) = rodio::Player::new();
+        let mut audio = Audio::new(
+            Arc::new(player),
+            AudioSpan::new(
+                0,
+                Duration::from_secs(0),
+                Duration::from_secs(30),
+                "f_0".to_string(),
+            ),
+            "f".to_string(),
+        );
+        let split_points: Vec<Duration> = [5, 10, 20, 25].map(Duration::from_secs).to_vec();
+        Audio::set_split_points(audio.spans_mut(), split_points);
+        audio.toggle_selected_split_points(Duration::from_secs(10));
+        audio.toggle_selected_split_points(Duration::from_secs(20));
+        assert_eq!(audio.split(), 2);
+        assert_eq!(audio.merge_with_next(0), 1);
+
+        let spans = audio.spans();
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].name(), "f_0");
+        assert_eq!(spans[0].end(), Duration::from_secs(20));
+        assert_eq!(
+            spans[0].split_points(),
+            [Duration::from_secs(5), Duration::from_secs(10)]
+        );
+        // nothing to submit for the merged range
+        assert_eq!(spans[0].edited_range_input(), (None, None));
+        // deleted spans stay on the timeline but are not merged with kept ones
+        audio.delete_span(2);
+        assert_eq!(audio.merge_spans(&[0, 2]), 0);
+        assert_eq!(audio.playback_end(), Some(Duration::from_secs(20)));
+    }
+    #[test]
+    fn analyze_range() {
+        let secs = Duration::from_secs;
+        let (player, _queue) = rodio::Player::new();
+        let mut audio = Audio::new(
+            Arc::new(player),
+            AudioSpan::new(0, secs(0), secs(60), "f_0".to_string()),
+            "f".to_string(),
+        );
+        Audio::set_split_points(audio.spans_mut(), vec![secs(10), secs(25), secs(50)]);
+        audio.set_selected_split_points(&[secs(10), secs(25)]);
+
+        audio.replace_split_points_in(
+            secs(20),
+            secs(40),
+            vec![
+                Silence::new(secs(21), secs(23)),
+                Silence::new(secs(34), secs(36)),
+            ],
+        );
+        assert_eq!(
+            audio.all_split_points(),
+            [secs(10), secs(22), secs(35), secs(50)]
+        );
+        assert_eq!(audio.selected_split_points(), [secs(10)]);
+        assert_eq!(audio.silences().len(), 2);
+        assert_eq!(audio.scores().len(), 2);
+    }
+    #[test]
+    fn trim_span() {
+        let secs = Duration::from_secs;
+        let (player, _queue) = rodio::Player::new();
+        let mut audio = Audio::new(
+            Arc::new(player),
+            AudioSpan::new(0, secs(0), secs(30), "f_0".to_string()),
+            "f".to_string(),
+        );
+        Audio::set_split_points(audio.spans_mut(), vec![secs(10), secs(20)]);
+        audio.toggle_selected_split_points(secs(10));
+        audio.split();
+
+        assert!(audio.trim_span(1, secs(12), secs(28)));
+        let ranges = |audio: &Audio| {
+            audio
+                .spans()
+                .iter()
+                .map(|s| (s.start().as_secs(), s.end().as_secs(), s.is_excluded()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ranges(&audio),
+            [
+                (0, 10, false),
+                (10, 12, true),
+                (12, 28, false),
+                (28, 30, true)
+            ]
+        );
+        assert_eq!(audio.spans()[2].split_points(), [secs(20)]);
+
+        // spans grow into excluded gaps only
+        assert!(audio.trim_span(1, secs(11), secs(30)));
+        assert!(audio.trim_span(0, secs(0), secs(15)));
+        assert_eq!(ranges(&audio), [(0, 11, false), (11, 30, false)]);
+        assert!(!audio.trim_span(1, secs(20), secs(20)));
+    }
+    #[test]
+    fn trim_silence_on_export() {
+        let millis = Duration::from_millis;
+        let span = AudioSpan::new(0, millis(10_000), millis(20_000), String::new());
+        let silences = [
+            Silence::new(millis(8_000), millis(12_000)),
+            Silence::new(millis(18_000), millis(22_000)),
+        ];
+        let mut options = ExportOptions::default();
+        assert_eq!(
+            options.segment_range(&span, &silences),
+            (millis(10_000), millis(20_000))
+        );
+        options.trim_silence = Some(millis(250));
+        assert_eq!(
+            options.segment_range(&span, &silences),
+            (millis(11_750), millis(18_250))
+        );
+        assert_eq!(
+            options.segment_range(&span, &[]),
+            (millis(10_000), millis(20_000))
+        );
+    }
+    #[test]
+    fn fades_on_export() {
+        let millis = Duration::from_millis;
+        let mut options = ExportOptions::default();
+        assert_eq!(options.filter(millis(10_000), millis(20_000), None), None);
+        options.fade_in = millis(500);
+        options.fade_out = millis(8_000);
+        options.fade_curve = FadeCurve::QuarterSine;
+        assert_eq!(
+            options
+                .filter(millis(10_000), millis(20_000), None)
+                .unwrap(),
+            "afade=t=in:st=10.000000:d=0.500000:curve=qsin,afade=t=out:st=15.000000:d=5.000000:curve=qsin"
+        );
+    }
+    #[test]
+    fn loudness_gain() {
+        let stderr = "[Parsed_loudnorm_0 @ 0x1]\n{\n\t\"input_i\" : \"-27.61\",\n\t\"input_tp\" : \"-4.47\",\n\t\"input_lra\" : \"18.06\"\n}\n";
+        let loudness = loudness::parse_loudnorm(stderr).unwrap();
+        assert_eq!(loudness, Loudness::new(-27.61, -4.47));
+        assert_eq!(loudness::parse_loudnorm("no json"), None);
+
+        let normalize = Normalize {
+            target: -16.0,
+            true_peak: -1.0,
+            mode: NormalizeMode::PerSegment,
+        };
+        // limited by the true peak ceiling
+        assert!((normalize.gain(loudness) - 3.47).abs() < 1e-4);
+        assert_eq!(normalize.gain(Loudness::new(-20.0, -10.0)), 4.0);
+        assert_eq!(
+            normalize.gain(Loudness::new(f32::NEG_INFINITY, f32::NEG_INFINITY)),
+            0.0
+        );
+
+        let combined = Loudness::combined(&[
+            (Loudness::new(-20.0, -3.0), Duration::from_secs(10)),
+            (Loudness::new(-20.0, -6.0), Duration::from_secs(30)),
+        ]);
+        assert!((combined.integrated() + 20.0).abs() < 1e-4);
+        assert_eq!(combined.true_peak(), -3.0);
+    }
+    #[test]
+    fn span_levels() {
+        let stderr = "[Parsed_ebur128_0 @ 0x1] t: 9.9  TARGET:-23 LUFS    M: -21.0 S: -22.1     I: -24.3 LUFS       LRA:   3.2 LU\n[Parsed_ebur128_0 @ 0x1] Summary:\n\n  Integrated loudness:\n    I:         -19.9 LUFS\n    Threshold: -30.2 LUFS\n\n  Loudness range:\n    LRA:         6.8 LU\n    Threshold:  -40.3 LUFS\n";
+        assert_eq!(loudness::parse_ebur128(stderr), Some((-19.9, 6.8)));
+        assert_eq!(loudness::parse_ebur128("I: -19.9 LUFS"), None);
+
+        let levels = Levels {
+            integrated: -19.9,
+            range: 6.8,
+            peak: 0.0,
+            rms: -22.0,
+            clipped: 3,
+        };
+        let mut span = AudioSpan::new(0, Duration::ZERO, Duration::from_secs(10), "a".into());
+        span.set_levels(levels);
+        assert_eq!(
+            loudness::levels_report(std::slice::from_ref(&span)),
+            "file,integrated_lufs,loudness_range_lu,peak_dbfs,rms_dbfs,clipped_samples\na,-19.9,6.8,0.0,-22.0,3\n"
+        );
+        // a changed range invalidates the measurement
+        span.set_range(Duration::ZERO, Duration::from_secs(5));
+        assert_eq!(span.levels(), None);
+
+        // the measurement of a range survives the reset and replay of an undo
+        let (player, _queue) = rodio::Player::new();
+        let mut audio = Audio::new(
+            Arc::new(player),
+            AudioSpan::new(0, Duration::ZERO, Duration::from_secs(10), "a".into()),
+            "a".into(),
+        );
+        let split = |audio: &mut Audio| {
+            Audio::set_split_points(audio.spans_mut(), vec![Duration::from_secs(4)]);
+            audio.toggle_selected_split_points(Duration::from_secs(4));
+            audio.split();
+        };
+        split(&mut audio);
+        let second = audio.spans()[1].id();
+        audio.set_levels(vec![(second, levels)]);
+        audio.reset();
+        assert_eq!(audio.spans()[0].levels(), None);
+        split(&mut audio);
+        assert_eq!(audio.spans()[0].levels(), None);
+        assert_eq!(audio.spans()[1].levels(), Some(levels));
+    }
+    #[test]
+    fn split_point_scores() {
+        let millis = Duration::from_millis;
+        let short = Silence::new(millis(1_000), millis(1_200));
+        let long = Silence::new(millis(5_000), millis(8_000));
+        assert!(short.score() < long.score());
+        assert!(long.score() < 1.0);
+        assert!(long.with_depth(5.0).score() < long.with_depth(20.0).score());
+        assert!((long.with_depth(40.0).score() - (1.0 + long.score()) / 2.0).abs() < 1e-6);
+
+        let (player, _queue) = rodio::Player::new();
+        let mut audio = Audio::new(
+            Arc::new(player),
+            AudioSpan::new(0, millis(0), millis(10_000), "a".into()),
+            "a".into(),
+        );
+        Audio::set_split_points(audio.spans_mut(), vec![millis(1_100), millis(6_500)]);
+        audio.set_scores(vec![(millis(1_100), 0.2), (millis(6_500), 0.9)]);
+        audio.toggle_selected_split_points(millis(1_100));
+        audio.split();
+        assert_eq!(audio.spans()[0].score(millis(1_100)), None);
+        assert_eq!(audio.spans()[1].score(millis(6_500)), Some(0.9));
+    }
+    #[test]
+    fn noise_floor() {
+        let mut levels = vec![f32::NEG_INFINITY; 50];
+        levels.extend([-60.2; 30]);
+        levels.extend([-20.4; 70]);
+        let noise_floor = analyze::estimate_noise_floor(&levels).unwrap();
+        assert_eq!(noise_floor.floor, -60.5);
+        assert_eq!(noise_floor.threshold, -50.5);
+        assert_eq!(
+            analyze::estimate_noise_floor(&[f32::NEG_INFINITY; 10]),
+            None
+        );
+    }
+    #[test]
+    fn generate_split_points() {
+        let secs = Duration::from_secs;
+        assert_eq!(
+            analyze::interval_points(secs(100), secs(30)),
+            [secs(30), secs(60), secs(90)]
+        );
+        assert_eq!(
+            analyze::interval_points(secs(90), secs(30)),
+            [secs(30), secs(60)]
+        );
+        assert_eq!(analyze::interval_points(secs(90), secs(0)), []);
+        assert_eq!(analyze::equal_parts(secs(90), 3), [secs(30), secs(60)]);
+        assert_eq!(analyze::equal_parts(secs(90), 1), []);
+        let silences = [
+            Silence::new(secs(24), secs(26)),
+            Silence::new(secs(33), secs(35)),
+            Silence::new(secs(70), secs(80)),
+        ];
+        assert_eq!(
+            analyze::snap_to_silences(&[secs(30), secs(60), secs(90)], &silences, secs(5)),
+            [secs(34), secs(60), secs(90)]
+        );
+    }
+    #[test]
+    fn bulk_selection() {
+        let secs = Duration::from_secs;
+        let points = [secs(10), secs(20), secs(30), secs(40), secs(50)];
+        let selected = [secs(20), secs(40)];
+        assert_eq!(Selection::All.apply(&points, &selected), points);
+        assert_eq!(Selection::None.apply(&points, &selected), []);
+        assert_eq!(
+            Selection::Invert.apply(&points, &selected),
+            [secs(10), secs(30), secs(50)]
+        );
+        assert_eq!(
+            Selection::EveryNth(2).apply(&points, &selected),
+            [secs(20), secs(40)]
+        );
+        assert_eq!(Selection::EveryNth(0).apply(&points, &selected), points);
+        assert_eq!(
+            Selection::Range(secs(35), secs(15)).apply(&points, &selected),
+            [secs(20), secs(30)]
+        );
+    }
+    #[test]
+    fn envelope_silences() {
+        let millis = Duration::from_millis;
+        // 50 ms windows
+        let mut levels = vec![-20.0; 40];
+        levels[10..20].fill(-60.0);
+        levels[25..27].fill(f32::NEG_INFINITY);
+        levels[35..].fill(-50.0);
+        let envelope = Envelope::new(levels.clone(), millis(50));
+        let silences = analyze::envelope_silences(&envelope, -40.0, millis(200));
+        let ranges: Vec<_> = silences.iter().map(|s| (s.start(), s.end())).collect();
+        assert_eq!(
+            ranges,
+            [(millis(500), millis(1_000)), (millis(1_750), millis(2_000))]
+        );
+        assert!(silences[0].score() > 0.0);
+        assert_eq!(envelope.level_at(millis(520)), Some(-60.0));
+        assert_eq!(envelope.level_at(millis(5_000)), None);
+        // windows of whole frames at a low sample rate are shorter than 50 ms
+        let envelope = Envelope::new(levels, millis(40));
+        let silences = analyze::envelope_silences(&envelope, -40.0, millis(200));
+        assert_eq!(
+            (silences[0].start(), silences[0].end()),
+            (millis(400), millis(800))
+        );
+        assert_eq!(envelope.level_at(millis(760)), Some(-60.0));
+        assert_eq!(envelope.level_at(millis(800)), Some(-20.0));
+    }
+    #[test]
+    fn sound_classes() {
+        let rate = 44_100.0;
+        let tone = |i: usize| 0.5 * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / rate).sin();
+        let music: Vec<f32> = (0..44_100).map(tone).collect();
+        assert_eq!(Features::new(&music).class(), SoundClass::Music);
+        // syllables of 125 ms with pauses in between
+        let speech: Vec<f32> = (0..44_100)
+            .map(|i| if (i / 5_512) % 2 == 0 { tone(i) } else { 0.0 })
+            .collect();
+        assert_eq!(Features::new(&speech).class(), SoundClass::Speech);
+        let mut seed = 1u32;
+        let noise: Vec<f32> = (0..44_100)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                (seed >> 8) as f32 / (1 << 23) as f32 - 1.0
+            })
+            .collect();
+        assert_eq!(Features::new(&noise).class(), SoundClass::Noise);
+        assert_eq!(Features::new(&[0.0; 44_100]).class(), SoundClass::Noise);
+
+        let secs = Duration::from_secs;
+        let (s, m) = (SoundClass::Speech, SoundClass::Music);
+        let regions = classify::regions(&classify::smooth(&[s, s, m, s, s, m, m]), secs(1));
+        assert_eq!(
+            regions,
+            [
+                ClassRegion::new(s, secs(0), secs(5)),
+                ClassRegion::new(m, secs(5), secs(7))
+            ]
+        );
+        assert_eq!(classify::class_changes(&regions), [secs(5)]);
+    }
+    #[test]
+    fn track_boundaries() {
+        let secs = Duration::from_secs;
+        // three tracks of 40, 60 and 50 blocks with a different spectrum each
+        let blocks: Vec<Vec<f32>> = [(40, 1.0), (60, 3.0), (50, 2.0)]
+            .iter()
+            .flat_map(|(n, level)| std::iter::repeat_n(vec![*level, -*level, 0.0], *n))
+            .collect();
+        let novelty = tracks::novelty(&blocks, 10);
+        assert_eq!(novelty[5], 0.0);
+        assert_eq!(novelty[145], 0.0);
+        let boundaries = tracks::pick_boundaries(&novelty, secs(1), secs(30));
+        let positions: Vec<Duration> = boundaries.iter().map(|(p, _)| *p).collect();
+        assert_eq!(positions, [secs(40), secs(100)]);
+        assert!((boundaries[1].1 - 0.5).abs() < 1e-4);
+        // too close to the stronger boundary
+        assert_eq!(
+            tracks::pick_boundaries(&novelty, secs(1), secs(70)),
+            [(secs(40), 1.0)]
+        );
+        assert_eq!(tracks::pick_boundaries(&[0.0; 10], secs(1), secs(1)), []);
+    }
+    #[test]
+    fn tone_markers() {
+        let rate = 44100.0;
+        let tone = |frequencies: &[f32]| -> Vec<f32> {
+            (0..1323)
+                .map(|i| {
+                    frequencies
+                        .iter()
+                        .map(|f| 0.25 * (2.0 * std::f32::consts::PI * f * i as f32 / rate).sin())
+                        .sum()
+                })
+                .collect()
+        };
+        let beep = MarkerTone::Sine(1000.0);
+        assert_eq!(
+            markers::detect_tone(&tone(&[1000.0]), rate, &beep),
+            Some(None)
+        );
+        assert_eq!(markers::detect_tone(&tone(&[440.0]), rate, &beep), None);
+        assert_eq!(markers::detect_tone(&[0.0; 1323], rate, &beep), None);
+        let dtmf = MarkerTone::Dtmf(vec!['5', '#']);
+        assert_eq!(
+            markers::detect_tone(&tone(&[770.0, 1336.0]), rate, &dtmf),
+            Some(Some('5'))
+        );
+        // a '1' is not one of the marker digits
+        assert_eq!(
+            markers::detect_tone(&tone(&[697.0, 1209.0]), rate, &dtmf),
+            None
+        );
+        assert_eq!(markers::detect_tone(&tone(&[1000.0]), rate, &dtmf), None);
+
+        let ms = Duration::from_millis;
+        // a single frame is too short, a new digit starts a new marker
+        let frames = [
+            None,
+            Some(None),
+            None,
+            Some(Some('5')),
+            Some(Some('5')),
+            Some(Some('#')),
+            Some(Some('#')),
+            Some(Some('#')),
+        ];
+        let found: Vec<(Duration, Duration)> = markers::markers(&frames, ms(30))
+            .iter()
+            .map(|m| (m.start(), m.end()))
+            .collect();
+        assert_eq!(found, [(ms(90), ms(150)), (ms(150), ms(240))]);
+
+        assert_eq!(MarkerTone::parse(" 1000 Hz ").unwrap(), beep);
+        assert_eq!(MarkerTone::parse("1000hz").unwrap(), beep);
+        assert_eq!(
+            MarkerTone::parse("#5").unwrap(),
+            MarkerTone::Dtmf(vec!['#', '5'])
+        );
+        assert_eq!(MarkerTone::parse("a").unwrap(), MarkerTone::Dtmf(vec!['A']));
+        // digits without a unit are DTMF, not a frequency
+        assert_eq!(MarkerTone::parse("5").unwrap(), MarkerTone::Dtmf(vec!['5']));
+        assert_eq!(MarkerTone::parse("0").unwrap(), MarkerTone::Dtmf(vec!['0']));
+        assert_eq!(
+            MarkerTone::parse("123").unwrap(),
+            MarkerTone::Dtmf(vec!['1', '2', '3'])
+        );
+        assert!(MarkerTone::parse("x").is_err());
+        assert!(MarkerTone::parse("0 Hz").is_err());
+        assert!(MarkerTone::parse("-5 Hz").is_err());
+        assert!(MarkerTone::parse("Hz").is_err());
+        assert!(MarkerTone::parse("").is_err());
+    }
+    #[test]
+    fn segment_constraints() {
+        let secs = Duration::from_secs;
+        let silence = |start: u64, end: u64| Silence::new(secs(start), secs(end));
+        let silences = vec![
+            silence(9, 11),
+            silence(14, 16),
+            silence(18, 22),
+            silence(38, 42),
+            silence(59, 61),
+        ];
+        let midpoints = |silences: Vec<Silence>| -> Vec<u64> {
+            silences.iter().map(|s| s.midpoint().as_secs()).collect()
+        };
+        assert_eq!(
+            midpoints(Constraints::default().apply(silences.clone(), secs(70))),
+            [10, 15, 20, 40, 60]
+        );
+        let min_length = Constraints {
+            min_segment_length: Some(secs(8)),
+            max_segments: None,
+        };
+        assert_eq!(
+            midpoints(min_length.apply(silences.clone(), secs(70))),
+            [20, 40, 60]
+        );
+        let max_segments = Constraints {
+            min_segment_length: None,
+            max_segments: Some(3),
+        };
+        assert_eq!(
+            midpoints(max_segments.apply(silences.clone(), secs(70))),
+            [20, 40]
+        );
+        let single = Constraints {
+            min_segment_length: None,
+            max_segments: Some(0),
+        };
+        assert_eq!(midpoints(single.apply(silences, secs(70))), []);
+    }
+    #[test]
+    fn target_length_cuts() {
+        let secs = Duration::from_secs;
+        let silence = |start: u64, end: u64| Silence::new(secs(start), secs(end));
+        let length = SegmentLength {
+            target: secs(100),
+            min: secs(80),
+            max: secs(120),
+        };
+        let silences = [
+            silence(49, 51),
+            // closer to the target, but a longer silence nearby wins
+            silence(96, 97),
+            silence(104, 112),
+            silence(150, 152),
+            silence(205, 207),
+            silence(299, 301),
+        ];
+        assert_eq!(
+            analyze::target_length_cuts(&silences, secs(320), &length),
+            [secs(108), secs(206)]
+        );
+        // the rest fits into one segment
+        assert_eq!(
+            analyze::target_length_cuts(&silences, secs(110), &length),
+            []
+        );
+        // without a silence in range the closest one is used
+        assert_eq!(
+            analyze::target_length_cuts(&[silence(149, 151)], secs(300), &length),
+            [secs(150)]
+        );
+        // a lone silence is cut only once, even without a min length
+        let no_min = SegmentLength {
+            min: Duration::ZERO,
+            ..length
+        };
+        assert_eq!(
+            analyze::target_length_cuts(&[silence(59, 61)], secs(400), &no_min),
+            [secs(60)]
+        );
+    }
+    #[test]
+    fn detect_defects() {
+        let mut detector = DefectDetector::new(1000);
+        let mut push = |sample: f32, frames: usize| {
+            for _ in 0..frames {
+                detector.push_frame(&[sample, sample * 0.5]);
+            }
+        };
+        push(0.5, 10);
+        push(1.0, 5);
+        push(0.5, 10);
+        push(1.0, 2);
+        push(0.5, 10);
+        push(0.0, 20);
+        push(0.01, 10);
+        push(0.0, 20);
+        push(0.01, 10);
+        push(0.0, 50);
+        let defects = detector.finish();
+        let ranges: Vec<(DefectKind, u64, u64)> = defects
+            .iter()
+            .map(|d| {
+                let millis = |t: Duration| t.as_millis() as u64;
+                (d.kind(), millis(d.start()), millis(d.end()))
+            })
+            .collect();
+        // short clipping, silence after quiet audio and silence at the end are ignored
+        assert_eq!(
+            ranges,
+            [
+                (DefectKind::Clipping, 10, 15),
+                (DefectKind::Dropout, 37, 57)
+            ]
+        );
+    }
+    #[test]
+    fn nearest_zero_crossing() {
+        let samples = [0.5, 0.2, -0.1, -0.4, -0.2, 0.3, 0.1];
+        assert_eq!(export::nearest_zero_crossing(&samples, 0), Some(2));
+        assert_eq!(export::nearest_zero_crossing(&samples, 4), Some(5));
+        assert_eq!(export::nearest_zero_crossing(&[0.1, 0.2], 1), None);
+    }
+    #[test]
+    fn settings_round_trip() {
+        let mut settings = Settings::parse(
+            "import_folder = /tmp/in\nthreshold = -30.5\npreview_gap = true\npreset.podcast = -42.0 0.4\npreset.radio = -50.0 0.2\n",
+        );
+        assert_eq!(settings.import_folder, Some("/tmp/in".into()));
+        assert_eq!(settings.export_folder, None);
+        assert_eq!(settings.threshold, "-30.5");
+        assert_eq!(settings.duration, "0.3");
+        assert!(settings.preview_gap);
+        assert_eq!(settings.presets[1], Preset::new("podcast", "-42.0", "0.4"));
+        assert_eq!(settings.presets[3], Preset::new("radio", "-50.0", "0.2"));
+
+        settings.export_folder = Some("/tmp/out".into());
+        assert_eq!(Settings::parse(&settings.to_string()), settings);
+    }
+    #[test]
+    fn parse_key_bindings() {
+        let key_bindings =
+            KeyBindings::parse("# comment\n\nCtrl+Shift+U = Undo\nshift+space = PlayPause\n")
+                .unwrap();
+        assert_eq!(
+            key_bindings.action(
+                &Key::Character("u".into()),
+                Modifiers::CTRL | Modifiers::SHIFT
+            ),
+            Some(Action::Undo)
+        );
+        assert_eq!(
+            key_bindings.action(&Key::Named(Named::Space), Modifiers::SHIFT),
+            Some(Action::PlayPause)
+        );
+        // the default bindings of rebound actions are dropped
+        assert_eq!(
+            key_bindings.action(&Key::Character("z".into()), Modifiers::CTRL),
+            None
+        );
+        assert_eq!(
+            key_bindings.action(&Key::Character("s".into()), Modifiers::empty()),
+            Some(Action::Split)
+        );
+    }
+    #[test]
+    fn parse_key_bindings_errors() {
+        let errors =
+            KeyBindings::parse("S = Jump\nHyper+S = Split\nS\nT = Split\nt = Undo").unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                BindingError::UnknownAction(1, "Jump".to_string()),
+                BindingError::UnknownKey(2, "Hyper".to_string()),
+                BindingError::Syntax(3),
+                BindingError::Duplicate(5, "T".to_string()),
+                BindingError::Taken(4, "T".to_string(), Action::ToggleSplitPoint),
+            ]
+        );
+        // taking over the key of Split is fine once Split has another one
+        let errors = KeyBindings::parse("S = Undo").unwrap_err();
+        assert_eq!(
+            errors,
+            vec![BindingError::Taken(1, "S".to_string(), Action::Split)]
+        );
+        let key_bindings = KeyBindings::parse(
+            "S = Undo
+Ctrl+S = Split",
+        )
+        .unwrap();
+        assert_eq!(
+            key_bindings.action(&Key::Character("s".into()), Modifiers::empty()),
+            Some(Action::Undo)
+        );
+    }
 }
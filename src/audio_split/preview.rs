@@ -0,0 +1,76 @@
+use std::time::{Duration, Instant};
+
+pub const GAP: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone)]
+pub struct Preview {
+    cut: Duration,
+    end: Duration,
+    return_to: Duration,
+    was_playing: bool,
+    gap: Option<Duration>,
+    gap_started: Option<Instant>,
+    gap_done: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PreviewStep {
+    Continue,
+    Pause,
+    Resume,
+    Finish {
+        return_to: Duration,
+        was_playing: bool,
+    },
+}
+
+impl Preview {
+    pub fn new(
+        cut: Duration,
+        roll: Duration,
+        return_to: Duration,
+        was_playing: bool,
+        gap: Option<Duration>,
+    ) -> Self {
+        Self {
+            cut,
+            end: cut + roll,
+            return_to,
+            was_playing,
+            gap,
+            gap_started: None,
+            gap_done: false,
+        }
+    }
+    pub fn start(cut: Duration, roll: Duration) -> Duration {
+        cut.saturating_sub(roll)
+    }
+    pub fn origin(&self) -> (Duration, bool) {
+        (self.return_to, self.was_playing)
+    }
+    // the end must not reach the point where `Tick` wraps playback to zero
+    pub fn clamp_end(&mut self, limit: Duration) {
+        self.end = self.end.min(limit);
+    }
+    pub fn step(&mut self, pos: Duration) -> PreviewStep {
+        if let Some(started) = self.gap_started {
+            if self.gap.is_some_and(|gap| started.elapsed() >= gap) {
+                self.gap_started = None;
+                self.gap_done = true;
+                return PreviewStep::Resume;
+            }
+            return PreviewStep::Continue;
+        }
+        if pos >= self.end {
+            PreviewStep::Finish {
+                return_to: self.return_to,
+                was_playing: self.was_playing,
+            }
+        } else if self.gap.is_some() && !self.gap_done && pos >= self.cut {
+            self.gap_started = Some(Instant::now());
+            PreviewStep::Pause
+        } else {
+            PreviewStep::Continue
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+// bulk changes of the split point selection, applied as one undo step
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Selection {
+    All,
+    None,
+    Invert,
+    // the Nth, 2Nth, ... split point
+    EveryNth(usize),
+    // replaces the selection, f.e. from a drag on the timeline
+    Range(Duration, Duration),
+}
+
+impl Selection {
+    // `split_points` must be sorted
+    pub fn apply(self, split_points: &[Duration], selected: &[Duration]) -> Vec<Duration> {
+        let points = split_points.iter().copied();
+        match self {
+            Selection::All => points.collect(),
+            Selection::None => Vec::new(),
+            Selection::Invert => points.filter(|p| !selected.contains(p)).collect(),
+            Selection::EveryNth(n) => points.skip(n.max(1) - 1).step_by(n.max(1)).collect(),
+            Selection::Range(a, b) => points
+                .filter(|p| a.min(b) <= *p && *p <= a.max(b))
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use std::{fmt, path::PathBuf};
+
+use crate::audio_split::{analyze::SplitMode, export::FadeCurve, loudness::NormalizeMode};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preset {
+    name: String,
+    threshold: String,
+    duration: String,
+}
+
+impl Preset {
+    pub fn new(name: &str, threshold: &str, duration: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            threshold: threshold.to_string(),
+            duration: duration.to_string(),
+        }
+    }
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Preset::new("audiobook", "-45.0", "0.8"),
+            Preset::new("podcast", "-40.0", "0.5"),
+            Preset::new("lecture", "-35.0", "1.5"),
+        ]
+    }
+    pub fn threshold(&self) -> &str {
+        &self.threshold
+    }
+    pub fn duration(&self) -> &str {
+        &self.duration
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+// stored as `key = value` lines in the config dir, see `utils::config_dir`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub import_folder: Option<PathBuf>,
+    pub export_folder: Option<PathBuf>,
+    pub threshold: String,
+    pub duration: String,
+    pub preview_duration: String,
+    pub preview_gap: bool,
+    pub trim_silence: bool,
+    pub trim_padding: String,
+    pub fade_in: String,
+    pub fade_out: String,
+    pub fade_curve: FadeCurve,
+    pub snap_to_zero_crossing: bool,
+    pub normalize: bool,
+    pub loudness_target: String,
+    pub true_peak: String,
+    pub normalize_mode: NormalizeMode,
+    pub min_segment_length: String,
+    pub max_segments: String,
+    pub split_mode: SplitMode,
+    pub interval: String,
+    pub parts: String,
+    pub snap_window: String,
+    pub min_score: String,
+    pub target_length_mode: bool,
+    pub target_length: String,
+    pub min_length: String,
+    pub max_length: String,
+    pub marker_tone: String,
+    pub remove_markers: bool,
+    pub presets: Vec<Preset>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            import_folder: None,
+            export_folder: None,
+            threshold: "-45.0".to_string(),
+            duration: "0.3".to_string(),
+            preview_duration: "2.0".to_string(),
+            preview_gap: false,
+            trim_silence: false,
+            trim_padding: "0.25".to_string(),
+            fade_in: "0.0".to_string(),
+            fade_out: "0.0".to_string(),
+            fade_curve: FadeCurve::default(),
+            snap_to_zero_crossing: false,
+            normalize: false,
+            loudness_target: "-16.0".to_string(),
+            true_peak: "-1.0".to_string(),
+            normalize_mode: NormalizeMode::default(),
+            min_segment_length: String::new(),
+            max_segments: String::new(),
+            split_mode: SplitMode::default(),
+            interval: "300".to_string(),
+            parts: "4".to_string(),
+            snap_window: "10".to_string(),
+            min_score: "50".to_string(),
+            target_length_mode: false,
+            target_length: "10".to_string(),
+            min_length: "8".to_string(),
+            max_length: "12".to_string(),
+            marker_tone: "1000 Hz".to_string(),
+            remove_markers: false,
+            presets: Preset::defaults(),
+        }
+    }
+}
+
+impl Settings {
+    // unknown keys and broken values are skipped, the file is rewritten on the next save
+    pub fn parse(text: &str) -> Self {
+        let mut settings = Self::default();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+            match key {
+                "import_folder" => settings.import_folder = Some(value.into()),
+                "export_folder" => settings.export_folder = Some(value.into()),
+                "threshold" => settings.threshold = value.to_string(),
+                "duration" => settings.duration = value.to_string(),
+                "preview_duration" => settings.preview_duration = value.to_string(),
+                "preview_gap" => {
+                    if let Ok(gap) = value.parse() {
+                        settings.preview_gap = gap;
+                    }
+                }
+                "trim_silence" => {
+                    if let Ok(trim) = value.parse() {
+                        settings.trim_silence = trim;
+                    }
+                }
+                "trim_padding" => settings.trim_padding = value.to_string(),
+                "fade_in" => settings.fade_in = value.to_string(),
+                "fade_out" => settings.fade_out = value.to_string(),
+                "fade_curve" => {
+                    if let Some(curve) = FadeCurve::from_ffmpeg_name(value) {
+                        settings.fade_curve = curve;
+                    }
+                }
+                "snap_to_zero_crossing" => {
+                    if let Ok(snap) = value.parse() {
+                        settings.snap_to_zero_crossing = snap;
+                    }
+                }
+                "normalize" => {
+                    if let Ok(normalize) = value.parse() {
+                        settings.normalize = normalize;
+                    }
+                }
+                "loudness_target" => settings.loudness_target = value.to_string(),
+                "true_peak" => settings.true_peak = value.to_string(),
+                "min_segment_length" => settings.min_segment_length = value.to_string(),
+                "max_segments" => settings.max_segments = value.to_string(),
+                "split_mode" => {
+                    if let Some(mode) = SplitMode::from_config_name(value) {
+                        settings.split_mode = mode;
+                    }
+                }
+                "interval" => settings.interval = value.to_string(),
+                "parts" => settings.parts = value.to_string(),
+                "snap_window" => settings.snap_window = value.to_string(),
+                "min_score" => settings.min_score = value.to_string(),
+                "target_length_mode" => {
+                    if let Ok(mode) = value.parse() {
+                        settings.target_length_mode = mode;
+                    }
+                }
+                "target_length" => settings.target_length = value.to_string(),
+                "min_length" => settings.min_length = value.to_string(),
+                "max_length" => settings.max_length = value.to_string(),
+                "marker_tone" => settings.marker_tone = value.to_string(),
+                "remove_markers" => {
+                    if let Ok(remove) = value.parse() {
+                        settings.remove_markers = remove;
+                    }
+                }
+                "normalize_mode" => {
+                    if let Some(mode) = NormalizeMode::from_config_name(value) {
+                        settings.normalize_mode = mode;
+                    }
+                }
+                _ => {
+                    if let Some(name) = key.strip_prefix("preset.")
+                        && let Some((threshold, duration)) = value.split_once(' ')
+                    {
+                        let preset = Preset::new(name, threshold.trim(), duration.trim());
+                        match settings.presets.iter_mut().find(|p| p.name == name) {
+                            Some(existing) => *existing = preset,
+                            None => settings.presets.push(preset),
+                        }
+                    }
+                }
+            }
+        }
+        settings
+    }
+}
+
+impl fmt::Display for Settings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# audio-split settings, rewritten by the app")?;
+        if let Some(folder) = &self.import_folder {
+            writeln!(f, "import_folder = {}", folder.display())?;
+        }
+        if let Some(folder) = &self.export_folder {
+            writeln!(f, "export_folder = {}", folder.display())?;
+        }
+        writeln!(f, "threshold = {}", self.threshold)?;
+        writeln!(f, "duration = {}", self.duration)?;
+        writeln!(f, "preview_duration = {}", self.preview_duration)?;
+        writeln!(f, "preview_gap = {}", self.preview_gap)?;
+        writeln!(f, "trim_silence = {}", self.trim_silence)?;
+        writeln!(f, "trim_padding = {}", self.trim_padding)?;
+        writeln!(f, "fade_in = {}", self.fade_in)?;
+        writeln!(f, "fade_out = {}", self.fade_out)?;
+        writeln!(f, "fade_curve = {}", self.fade_curve.ffmpeg_name())?;
+        writeln!(f, "snap_to_zero_crossing = {}", self.snap_to_zero_crossing)?;
+        writeln!(f, "normalize = {}", self.normalize)?;
+        writeln!(f, "loudness_target = {}", self.loudness_target)?;
+        writeln!(f, "true_peak = {}", self.true_peak)?;
+        writeln!(f, "normalize_mode = {}", self.normalize_mode.config_name())?;
+        writeln!(f, "min_segment_length = {}", self.min_segment_length)?;
+        writeln!(f, "max_segments = {}", self.max_segments)?;
+        writeln!(f, "split_mode = {}", self.split_mode.config_name())?;
+        writeln!(f, "interval = {}", self.interval)?;
+        writeln!(f, "parts = {}", self.parts)?;
+        writeln!(f, "snap_window = {}", self.snap_window)?;
+        writeln!(f, "min_score = {}", self.min_score)?;
+        writeln!(f, "target_length_mode = {}", self.target_length_mode)?;
+        writeln!(f, "target_length = {}", self.target_length)?;
+        writeln!(f, "min_length = {}", self.min_length)?;
+        writeln!(f, "max_length = {}", self.max_length)?;
+        writeln!(f, "marker_tone = {}", self.marker_tone)?;
+        writeln!(f, "remove_markers = {}", self.remove_markers)?;
+        for preset in &self.presets {
+            writeln!(
+                f,
+                "preset.{} = {} {}",
+                preset.name, preset.threshold, preset.duration
+            )?;
+        }
+        Ok(())
+    }
+}
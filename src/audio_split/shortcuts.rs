@@ -0,0 +1,286 @@
+use std::{fmt, str::FromStr};
+
+use iced::keyboard::{Key, Modifiers, key::Named};
+use strum_macros::EnumString;
+
+// named keys that can be used in the key bindings file
+const NAMED_KEYS: &[Named] = &[
+    Named::Space,
+    Named::Enter,
+    Named::Tab,
+    Named::Escape,
+    Named::Backspace,
+    Named::Delete,
+    Named::Insert,
+    Named::Home,
+    Named::End,
+    Named::PageUp,
+    Named::PageDown,
+    Named::ArrowUp,
+    Named::ArrowDown,
+    Named::ArrowLeft,
+    Named::ArrowRight,
+    Named::F1,
+    Named::F2,
+    Named::F3,
+    Named::F4,
+    Named::F5,
+    Named::F6,
+    Named::F7,
+    Named::F8,
+    Named::F9,
+    Named::F10,
+    Named::F11,
+    Named::F12,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString)]
+pub enum Action {
+    PlayPause,
+    SeekForward,
+    SeekBackward,
+    NextSplitPoint,
+    PreviousSplitPoint,
+    ToggleSplitPoint,
+    Split,
+    DeleteSpan,
+    Undo,
+    Redo,
+    FocusSpanName,
+    Help,
+}
+
+impl Action {
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::PlayPause => "play / pause",
+            Action::SeekForward => "seek forward 5 s",
+            Action::SeekBackward => "seek backward 5 s",
+            Action::NextSplitPoint => "jump to the next split point",
+            Action::PreviousSplitPoint => "jump to the previous split point",
+            Action::ToggleSplitPoint => "select the split point under the playhead",
+            Action::Split => "split at the selected split points",
+            Action::DeleteSpan => "delete the span under the playhead",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::FocusSpanName => "rename the span under the playhead",
+            Action::Help => "show / hide this help",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBinding {
+    key: Key,
+    modifiers: Modifiers,
+}
+
+impl KeyBinding {
+    pub fn new(key: Key, modifiers: Modifiers) -> Self {
+        Self { key, modifiers }
+    }
+    pub fn matches(&self, key: &Key, modifiers: Modifiers) -> bool {
+        self.modifiers == modifiers
+            && match (&self.key, key) {
+                (Key::Character(a), Key::Character(b)) => a.eq_ignore_ascii_case(b),
+                (a, b) => a == b,
+            }
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split('+').map(str::trim).collect();
+        let (key, modifier_names) = parts.split_last().ok_or_else(|| s.to_string())?;
+        let mut modifiers = Modifiers::empty();
+        for name in modifier_names {
+            modifiers |= match name.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => Modifiers::CTRL,
+                "alt" => Modifiers::ALT,
+                "shift" => Modifiers::SHIFT,
+                "logo" | "super" | "cmd" => Modifiers::LOGO,
+                _ => return Err(name.to_string()),
+            };
+        }
+        let key = if key.chars().count() == 1 {
+            Key::Character(key.to_lowercase().into())
+        } else {
+            NAMED_KEYS
+                .iter()
+                .find(|n| format!("{n:?}").eq_ignore_ascii_case(key))
+                .map(|n| Key::Named(*n))
+                .ok_or_else(|| key.to_string())?
+        };
+        Ok(Self { key, modifiers })
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.control() {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.alt() {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.logo() {
+            write!(f, "Logo+")?;
+        }
+        if self.modifiers.shift() {
+            write!(f, "Shift+")?;
+        }
+        match &self.key {
+            Key::Character(c) => write!(f, "{}", c.to_uppercase()),
+            Key::Named(named) => write!(f, "{named:?}"),
+            Key::Unidentified => write!(f, "?"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct KeyBindings {
+    bindings: Vec<(KeyBinding, Action)>,
+}
+
+impl KeyBindings {
+    pub fn action(&self, key: &Key, modifiers: Modifiers) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(b, _)| b.matches(key, modifiers))
+            .map(|(_, a)| *a)
+    }
+    pub fn bindings(&self) -> &[(KeyBinding, Action)] {
+        &self.bindings
+    }
+    // one `<binding> = <action>` per line, f.e. `Ctrl+Shift+Z = Redo`;
+    // actions bound in the file lose their default bindings, a default binding can only be
+    // taken over when its action is bound to another key in the file
+    pub fn parse(text: &str) -> Result<Self, Vec<BindingError>> {
+        let mut errors = Vec::new();
+        let mut parsed: Vec<(KeyBinding, Action)> = Vec::new();
+        let mut line_numbers = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((binding, action)) = line.rsplit_once('=') else {
+                errors.push(BindingError::Syntax(line_number));
+                continue;
+            };
+            let binding = match binding.parse::<KeyBinding>() {
+                Ok(binding) => binding,
+                Err(key) => {
+                    errors.push(BindingError::UnknownKey(line_number, key));
+                    continue;
+                }
+            };
+            let Ok(action) = Action::from_str(action.trim()) else {
+                errors.push(BindingError::UnknownAction(
+                    line_number,
+                    action.trim().to_string(),
+                ));
+                continue;
+            };
+            if parsed.iter().any(|(b, _)| *b == binding) {
+                errors.push(BindingError::Duplicate(line_number, binding.to_string()));
+                continue;
+            }
+            parsed.push((binding, action));
+            line_numbers.push(line_number);
+        }
+        let defaults = Self::default().bindings;
+        for ((binding, action), line_number) in parsed.iter().zip(line_numbers) {
+            if let Some((_, taken)) = defaults.iter().find(|(b, a)| {
+                b == binding && a != action && parsed.iter().all(|(_, parsed_a)| parsed_a != a)
+            }) {
+                errors.push(BindingError::Taken(
+                    line_number,
+                    binding.to_string(),
+                    *taken,
+                ));
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        let mut bindings = defaults;
+        bindings.retain(|(b, a)| {
+            !parsed
+                .iter()
+                .any(|(parsed_b, parsed_a)| parsed_a == a || parsed_b == b)
+        });
+        bindings.extend(parsed);
+        Ok(Self { bindings })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BindingError {
+    Syntax(usize),
+    UnknownKey(usize, String),
+    UnknownAction(usize, String),
+    Duplicate(usize, String),
+    // the key of a default binding whose action is not bound elsewhere
+    Taken(usize, String, Action),
+}
+
+impl fmt::Display for BindingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindingError::Syntax(line) => {
+                write!(f, "line {line}: expected `<key> = <action>`")
+            }
+            BindingError::UnknownKey(line, key) => write!(f, "line {line}: unknown key `{key}`"),
+            BindingError::UnknownAction(line, action) => {
+                write!(f, "line {line}: unknown action `{action}`")
+            }
+            BindingError::Duplicate(line, binding) => {
+                write!(f, "line {line}: `{binding}` is bound twice")
+            }
+            BindingError::Taken(line, binding, action) => write!(
+                f,
+                "line {line}: `{binding}` is the default key of {action:?}, please bind it to \
+                 another key"
+            ),
+        }
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let none = Modifiers::empty();
+        let char = |c: &str| Key::Character(c.into());
+        let bindings = vec![
+            (Key::Named(Named::Space), none, Action::PlayPause),
+            (Key::Named(Named::ArrowRight), none, Action::SeekForward),
+            (Key::Named(Named::ArrowLeft), none, Action::SeekBackward),
+            (
+                Key::Named(Named::ArrowRight),
+                Modifiers::SHIFT,
+                Action::NextSplitPoint,
+            ),
+            (
+                Key::Named(Named::ArrowLeft),
+                Modifiers::SHIFT,
+                Action::PreviousSplitPoint,
+            ),
+            (char("t"), none, Action::ToggleSplitPoint),
+            (char("s"), none, Action::Split),
+            (Key::Named(Named::Delete), none, Action::DeleteSpan),
+            (char("z"), Modifiers::CTRL, Action::Undo),
+            (char("y"), Modifiers::CTRL, Action::Redo),
+            (char("z"), Modifiers::CTRL | Modifiers::SHIFT, Action::Redo),
+            (Key::Named(Named::F2), none, Action::FocusSpanName),
+            (Key::Named(Named::F1), none, Action::Help),
+        ];
+        Self {
+            bindings: bindings
+                .into_iter()
+                .map(|(key, modifiers, action)| (KeyBinding::new(key, modifiers), action))
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use std::{path::PathBuf, time::Duration};
+
+use crate::audio_split::{
+    classify::{FRAME_SIZE, for_each_window, power_spectrum},
+    error::Error,
+};
+
+// one spectrum per block, the resolution of the boundaries
+pub const BLOCK: Duration = Duration::from_millis(500);
+const BANDS: usize = 24;
+// blocks compared before and after a candidate, 10 s each
+const CONTEXT: usize = 20;
+// a mix rarely has tracks shorter than this
+pub const MIN_TRACK_LENGTH: Duration = Duration::from_secs(30);
+// peaks have to stand out by this many standard deviations of the novelty
+const PEAK_DEVIATIONS: f32 = 1.0;
+
+// log energy in log spaced bands of every `BLOCK`
+pub fn band_energies(samples: &[f32]) -> Vec<f32> {
+    let mut power = vec![0.0; FRAME_SIZE / 2 - 1];
+    for frame in samples.chunks_exact(FRAME_SIZE) {
+        for (sum, p) in power.iter_mut().zip(power_spectrum(frame)) {
+            *sum += p;
+        }
+    }
+    let edges: Vec<usize> = (0..=BANDS)
+        .map(|b| (power.len() as f32).powf(b as f32 / BANDS as f32) as usize)
+        .collect();
+    edges
+        .windows(2)
+        .map(|e| {
+            let band = &power[e[0].min(power.len() - 1)..e[1].max(e[0] + 1).min(power.len())];
+            (band.iter().sum::<f32>() / band.len() as f32 + 1e-10).log10()
+        })
+        .collect()
+}
+
+// distance between the mean spectrum of the `context` blocks before and after every block
+pub fn novelty(blocks: &[Vec<f32>], context: usize) -> Vec<f32> {
+    let mean = |range: &[Vec<f32>]| -> Vec<f32> {
+        let mut mean = vec![0.0; range[0].len()];
+        for block in range {
+            for (m, v) in mean.iter_mut().zip(block) {
+                *m += v / range.len() as f32;
+            }
+        }
+        mean
+    };
+    (0..blocks.len())
+        .map(|i| {
+            if i < context || i + context > blocks.len() {
+                return 0.0;
+            }
+            let (before, after) = (mean(&blocks[i - context..i]), mean(&blocks[i..i + context]));
+            before
+                .iter()
+                .zip(&after)
+                .map(|(b, a)| (b - a).powi(2))
+                .sum::<f32>()
+                .sqrt()
+        })
+        .collect()
+}
+
+// strongest novelty peaks at least `min_distance` apart, scored relative to the strongest
+pub fn pick_boundaries(
+    novelty: &[f32],
+    block: Duration,
+    min_distance: Duration,
+) -> Vec<(Duration, f32)> {
+    let max = novelty.iter().copied().fold(0.0, f32::max);
+    if max <= 0.0 {
+        return Vec::new();
+    }
+    let mean = novelty.iter().sum::<f32>() / novelty.len() as f32;
+    let deviation =
+        (novelty.iter().map(|n| (n - mean).powi(2)).sum::<f32>() / novelty.len() as f32).sqrt();
+    let mut peaks: Vec<(usize, f32)> = novelty
+        .iter()
+        .enumerate()
+        .filter(|(i, n)| {
+            **n > mean + PEAK_DEVIATIONS * deviation
+                && (*i == 0 || novelty[i - 1] < **n)
+                && novelty.get(i + 1).is_none_or(|next| next <= *n)
+        })
+        .map(|(i, n)| (i, *n))
+        .collect();
+    peaks.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let min_blocks = (min_distance.as_secs_f64() / block.as_secs_f64()) as usize;
+    let mut picked: Vec<(usize, f32)> = Vec::new();
+    for (i, n) in peaks {
+        if picked.iter().all(|(p, _)| p.abs_diff(i) >= min_blocks) {
+            picked.push((i, n));
+        }
+    }
+    picked.sort_by_key(|(i, _)| *i);
+    picked
+        .into_iter()
+        .map(|(i, n)| (block * i as u32, n / max))
+        .collect()
+}
+
+// proposed boundaries between tracks that follow each other without a silence
+pub async fn detect_track_boundaries(path: PathBuf) -> Result<Vec<(Duration, f32)>, Error> {
+    tokio::task::spawn_blocking(move || {
+        let mut blocks = Vec::new();
+        for_each_window(path, BLOCK, |samples, _| {
+            if samples.len() >= FRAME_SIZE {
+                blocks.push(band_energies(samples));
+            }
+        })?;
+        Ok(pick_boundaries(
+            &novelty(&blocks, CONTEXT),
+            BLOCK,
+            MIN_TRACK_LENGTH,
+        ))
+    })
+    .await
+    .unwrap()
+}
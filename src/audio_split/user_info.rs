@@ -13,11 +13,51 @@ pub mod info {
     pub const AUDIO_LOADED: &str = "Audio file loaded.";
     pub const SPLIT_POINTS_DETECTED: &str = "Detected {} potential split point(s).";
     pub const AUDIO_SPLIT_INTO_PARTS: &str = "Split performed at {} point(s).";
+    pub const SPANS_MERGED: &str = "Merged {} span(s).";
+    pub const PREVIEW_CUT: &str = "Previewing cut at {} s.";
+    pub const LOOP_START_SET: &str = "Loop start set at {} s. Press B to set the loop end.";
+    pub const LOOP_RANGE_SET: &str = "Looping from {} s to {} s.";
+    pub const THRESHOLD_ESTIMATED: &str =
+        "Estimated the noise floor at {} dB and set the threshold to {} dB.";
+    pub const RANGE_ANALYZED: &str = "Detected {} potential split point(s) between {} s and {} s.";
+    pub const ANALYSIS_CANCELLED: &str = "Analysis cancelled.";
+    pub const CLASSIFIED: &str = "Found {} region(s): {} speech, {} music and {} noise.";
+    pub const SPLIT_POINTS_SELECTED: &str = "{} split point(s) selected.";
+    pub const SELECTED_ABOVE_SCORE: &str =
+        "Selected {} split point(s) with a score of at least {} %.";
+    pub const SPLIT_POINTS_GENERATED: &str = "Generated {} split point(s).";
+    pub const TRACK_BOUNDARIES_DETECTED: &str = "Proposed {} track boundary(ies).";
+    pub const MARKERS_DETECTED: &str = "Found {} marker tone(s).";
+    pub const SPLIT_POINTS_CONSTRAINED: &str =
+        "Kept {} potential split point(s), dropped {} to meet the segment constraints.";
+    pub const TARGET_LENGTH_SELECTED: &str =
+        "Selected {} of {} potential split point(s) for the target length.";
+    pub const LEVELS_MEASURED: &str = "Measured the levels of {} span(s), {} of them clip.";
+    pub const LOUDNESS_NORMALIZED: &str =
+        "Normalized {} file(s) with a gain of {} dB to {} dB, see {} in the export folder.";
 }
 
 pub mod warning {
     pub const NO_AUDIO_LOADED: &str = "No audio file loaded. Please open an audio file first.";
     pub const NO_SPLIT_POINT_SELECTED: &str = "No split point selected. Please analyze the audio first and then select split points to cut.";
+    pub const NO_SPLIT_POINT_AT_PLAYHEAD: &str =
+        "No split point at the playhead. Please jump to a split point first.";
+    pub const NO_ADJACENT_SPANS: &str =
+        "Nothing to merge. Please select at least two adjacent spans.";
+    pub const INVALID_TRIM_RANGE: &str =
+        "Invalid span range. A span can only grow into deleted neighbours and must not be empty.";
+    pub const NOT_CLASSIFIED: &str =
+        "No class changes. Please classify the audio first, it must contain more than one class.";
+    pub const NO_NOISE_FLOOR: &str =
+        "No noise floor found. The audio seems to be digital silence only.";
+    pub const INVALID_ANALYSIS_RANGE: &str =
+        "Invalid analysis range. The end must be after the start and within the audio.";
+    pub const INVALID_SEGMENT_LENGTH: &str =
+        "Invalid segment length. The min must be positive and the target between min and max.";
+    pub const INVALID_LOOP_RANGE: &str =
+        "Invalid loop range. Please set the loop start with A before the loop end with B.";
+    pub const NO_MARKERS_FOUND: &str =
+        "No marker tones found. Please check the tone frequency or DTMF digits.";
     pub const NO_SPLIT_POINTS_FOUND: &str =
         "No Split Points found. Please try to increase the db threshold or decrease duration.";
 }
@@ -1,6 +1,21 @@
-use std::{fs::File, path::PathBuf, sync::Arc, time::Duration};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
-use crate::audio_split::{audio::Audio, audio_span::AudioSpan, error::Error};
+use crate::audio_split::{
+    analyze::Silence,
+    audio::Audio,
+    audio_span::AudioSpan,
+    debug_id::DebugId,
+    error::{Error, ErrorKind},
+    export::{self, ExportOptions},
+    loudness::{self, Loudness, NormalizeMode, SegmentGain},
+    settings::Settings,
+    shortcuts::KeyBindings,
+};
 use rfd::AsyncFileDialog;
 use rodio::{Player, Source};
 use tokio::process::Command;
@@ -28,38 +43,169 @@ pub async fn open_export_folder_dialog(starting_path: Option<PathBuf>) -> Option
         .and_then(|h| h.path().to_str().map(|s| s.to_string()))
 }
 
+// the loudness report lists measured loudness and applied gain per exported file
+pub const LOUDNESS_REPORT: &str = "loudness_report.csv";
+// written when the levels of the exported spans were measured
+pub const LEVELS_REPORT: &str = "levels_report.csv";
+
 pub async fn save_audio_files(
     source: PathBuf,
     export_path: PathBuf,
     path_extension: PathBuf,
     spans: Vec<AudioSpan>,
-) -> Result<(), Error> {
+    silences: Vec<Silence>,
+    options: ExportOptions,
+) -> Result<Vec<SegmentGain>, Error> {
+    if spans.iter().any(|s| s.levels().is_some()) {
+        tokio::fs::create_dir_all(&export_path).await?;
+        tokio::fs::write(
+            export_path.join(LEVELS_REPORT),
+            loudness::levels_report(&spans),
+        )
+        .await?;
+    }
+    let mut segments = Vec::new();
     for span in spans {
-        let mut export_path = export_path.join(span.name());
+        let (mut start, mut end) = options.segment_range(&span, &silences);
+        if options.snap_to_zero_crossing {
+            start = snap_to_zero_crossing(&source, start).await?;
+            end = snap_to_zero_crossing(&source, end).await?;
+        }
+        segments.push((span.name().to_string(), start, end));
+    }
+    let mut gains = Vec::new();
+    if let Some(normalize) = options.normalize {
+        let mut measured = Vec::new();
+        for (_, start, end) in &segments {
+            let loudness = loudness::measure_loudness(&source, *start, *end).await?;
+            measured.push((loudness, *end - *start));
+        }
+        let whole_set = normalize.gain(Loudness::combined(&measured));
+        for ((name, _, _), (loudness, _)) in segments.iter().zip(measured) {
+            let gain = match normalize.mode {
+                NormalizeMode::PerSegment => normalize.gain(loudness),
+                NormalizeMode::WholeSet => whole_set,
+            };
+            gains.push(SegmentGain {
+                file: name.clone(),
+                loudness,
+                gain,
+            });
+        }
+    }
+    for (index, (name, start, end)) in segments.into_iter().enumerate() {
+        let mut export_path = export_path.join(name);
         export_path.add_extension(&path_extension);
         let base = export_path.parent().unwrap();
         tokio::fs::create_dir_all(base).await.unwrap();
-        let output = Command::new("ffmpeg")
+        let mut command = Command::new("ffmpeg");
+        command
             .arg("-hide_banner")
             .arg("-nostats")
             .arg("-y")
             .arg("-i")
             .arg(&source)
             .arg("-ss")
-            .arg(fmt_duration(span.start()))
+            .arg(fmt_duration(start))
             .arg("-to")
-            .arg(fmt_duration(span.end()))
-            .arg(&export_path)
-            .output()
-            .await
-            .unwrap();
+            .arg(fmt_duration(end));
+        let gain = gains.get(index).map(|g| g.gain);
+        if let Some(filter) = options.filter(start, end, gain) {
+            command.arg("-af").arg(filter);
+        }
+        let output = command.arg(&export_path).output().await.unwrap();
         assert!(output.status.success());
     }
+    if !gains.is_empty() {
+        tokio::fs::write(export_path.join(LOUDNESS_REPORT), loudness::report(&gains)).await?;
+    }
+    Ok(gains)
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))
+        .map(|p| p.join("audio-split"))
+}
+
+pub async fn load_key_bindings() -> Result<KeyBindings, Error> {
+    let Some(path) = config_dir().map(|p| p.join("key_bindings.conf")) else {
+        return Ok(KeyBindings::default());
+    };
+    match tokio::fs::read_to_string(path).await {
+        Ok(text) => KeyBindings::parse(&text)
+            .map_err(|e| Error::new(ErrorKind::KeyBindings(e), DebugId::ErrorKeyBindings)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(KeyBindings::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub async fn load_settings() -> Result<Settings, Error> {
+    let Some(path) = config_dir().map(|p| p.join("settings.conf")) else {
+        return Ok(Settings::default());
+    };
+    match tokio::fs::read_to_string(path).await {
+        Ok(text) => Ok(Settings::parse(&text)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Settings::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub async fn save_settings(settings: Settings) -> Result<(), Error> {
+    let Some(dir) = config_dir() else {
+        return Ok(());
+    };
+    tokio::fs::create_dir_all(&dir).await?;
+    tokio::fs::write(dir.join("settings.conf"), settings.to_string()).await?;
     Ok(())
 }
 
+// samples around a cut that are searched for a zero crossing
+const ZERO_CROSSING_WINDOW: Duration = Duration::from_millis(10);
+const ZERO_CROSSING_SAMPLE_RATE: u32 = 48_000;
+
+// moves `pos` to the closest zero crossing of the downmixed signal, keeps `pos`
+// when there is none in the window
+async fn snap_to_zero_crossing(source: &Path, pos: Duration) -> Result<Duration, Error> {
+    let window_start = pos.saturating_sub(ZERO_CROSSING_WINDOW);
+    let output = Command::new("ffmpeg")
+        .arg("-hide_banner")
+        .arg("-nostats")
+        .arg("-ss")
+        .arg(fmt_duration(window_start))
+        .arg("-i")
+        .arg(source)
+        .arg("-t")
+        .arg(fmt_duration(ZERO_CROSSING_WINDOW * 2))
+        .arg("-ac")
+        .arg("1")
+        .arg("-ar")
+        .arg(ZERO_CROSSING_SAMPLE_RATE.to_string())
+        .arg("-f")
+        .arg("f32le")
+        .arg("-")
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Ok(pos);
+    }
+    let samples: Vec<f32> = output
+        .stdout
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+    let rate = ZERO_CROSSING_SAMPLE_RATE as f64;
+    let center = ((pos - window_start).as_secs_f64() * rate) as usize;
+    Ok(match export::nearest_zero_crossing(&samples, center) {
+        Some(index) => window_start + Duration::from_secs_f64(index as f64 / rate),
+        None => pos,
+    })
+}
+
 fn fmt_duration(duration: Duration) -> String {
-    format!("{:.6}", duration.as_secs_f32())
+    format!("{:.6}", duration.as_secs_f64())
 }
 
 pub async fn open_audio_file(
@@ -15,7 +15,11 @@ fn main() -> iced::Result {
     #[cfg(not(target_os = "linux"))]
     let platform_specific = window::settings::PlatformSpecific::default();
     iced::application(
-        || AudioSplit::init(RodioPlayer::init().unwrap()),
+        || {
+            let audio_split = AudioSplit::init(RodioPlayer::init().unwrap());
+            let task = audio_split.load_config();
+            (audio_split, task)
+        },
         AudioSplit::update,
         AudioSplit::view,
     )
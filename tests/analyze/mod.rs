@@ -43,3 +43,25 @@ async fn no_splits_detected() {
     let mut ui = simulator(audio_split.view());
     ui.find(DebugId::WarningNoSplitPointFound.id()).unwrap();
 }
+
+#[tokio::test]
+async fn cancel_analysis() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+    // the analysis is left running
+    let _ = audio_split.update(Message::Analyze);
+
+    let mut ui = simulator(audio_split.view());
+    ui.click(DebugId::ButtonCancelAnalysis.id()).unwrap();
+    for message in ui.into_messages() {
+        let task = audio_split.update(message);
+        execute_tasks(task, &mut audio_split).await;
+    }
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::InfoAnalysisCancelled.id()).unwrap();
+    assert!(ui.find(DebugId::ButtonCancelAnalysis.id()).is_err());
+}
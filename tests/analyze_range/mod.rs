@@ -0,0 +1,44 @@
+use crate::{execute_tasks, init};
+use audio_split::*;
+use iced_test::simulator;
+
+#[tokio::test]
+async fn invalid_range() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+
+    let task = audio_split.update(Message::UpdateAnalyzeFrom("20".into()));
+    execute_tasks(task, &mut audio_split).await;
+    let task = audio_split.update(Message::UpdateAnalyzeTo("10".into()));
+    execute_tasks(task, &mut audio_split).await;
+
+    let mut ui = simulator(audio_split.view());
+    ui.click(DebugId::ButtonAnalyzeRange.id()).unwrap();
+    for message in ui.into_messages() {
+        let task = audio_split.update(message);
+        execute_tasks(task, &mut audio_split).await;
+    }
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::WarningInvalidAnalysisRange.id()).unwrap();
+}
+
+#[tokio::test]
+async fn range_without_audio() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AnalyzeSpan(0));
+    execute_tasks(task, &mut audio_split).await;
+    let task = audio_split.update(Message::UpdateAnalyzeFrom("0".into()));
+    execute_tasks(task, &mut audio_split).await;
+    let task = audio_split.update(Message::UpdateAnalyzeTo("10".into()));
+    execute_tasks(task, &mut audio_split).await;
+    let task = audio_split.update(Message::AnalyzeInputRange);
+    execute_tasks(task, &mut audio_split).await;
+
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::WarningNoAudioLoaded.id()).unwrap();
+}
@@ -0,0 +1,42 @@
+use crate::{execute_tasks, init};
+use audio_split::*;
+use iced_test::simulator;
+
+#[tokio::test]
+async fn classify() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+
+    let mut ui = simulator(audio_split.view());
+    ui.click(DebugId::ButtonClassify.id()).unwrap();
+    for message in ui.into_messages() {
+        let task = audio_split.update(message);
+        execute_tasks(task, &mut audio_split).await;
+    }
+    let mut ui = simulator(audio_split.view());
+    // speech with a few pauses that are quiet enough to count as noise
+    ui.find(DebugId::InfoClassified(7).id()).unwrap();
+}
+
+#[tokio::test]
+async fn split_without_classes() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+
+    let mut ui = simulator(audio_split.view());
+    ui.click(DebugId::ButtonSplitAtClassChanges.id()).unwrap();
+    for message in ui.into_messages() {
+        let task = audio_split.update(message);
+        execute_tasks(task, &mut audio_split).await;
+    }
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::WarningNotClassified.id()).unwrap();
+}
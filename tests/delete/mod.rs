@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use crate::{execute_tasks, init};
+use audio_split::*;
+use iced_test::simulator;
+
+#[tokio::test]
+async fn delete_and_restore_span() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+
+    let task = audio_split.update(Message::ClickSplitPoint(Duration::from_secs_f32(22.10245)));
+    execute_tasks(task, &mut audio_split).await;
+    let task = audio_split.update(Message::Split);
+    execute_tasks(task, &mut audio_split).await;
+
+    let mut ui = simulator(audio_split.view());
+    ui.click(DebugId::ButtonDelete(1).id()).unwrap();
+    for message in ui.into_messages() {
+        let task = audio_split.update(message);
+        execute_tasks(task, &mut audio_split).await;
+    }
+
+    let mut ui = simulator(audio_split.view());
+    ui.click(DebugId::ButtonRestore(1).id()).unwrap();
+    for message in ui.into_messages() {
+        let task = audio_split.update(message);
+        execute_tasks(task, &mut audio_split).await;
+    }
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::ButtonDelete(1).id()).unwrap();
+}
+
+#[tokio::test]
+async fn undo_delete_span() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+
+    let task = audio_split.update(Message::DeleteAudioSpan(0));
+    execute_tasks(task, &mut audio_split).await;
+    {
+        let mut ui = simulator(audio_split.view());
+        ui.find(DebugId::ButtonRestore(0).id()).unwrap();
+    }
+
+    let task = audio_split.update(Message::Undo);
+    execute_tasks(task, &mut audio_split).await;
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::ButtonDelete(0).id()).unwrap();
+}
@@ -0,0 +1,74 @@
+use crate::{execute_tasks, init};
+use audio_split::*;
+use iced_test::simulator;
+
+#[tokio::test]
+async fn equal_parts() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+
+    let task = audio_split.update(Message::SelectSplitMode(SplitMode::EqualParts));
+    execute_tasks(task, &mut audio_split).await;
+    let task = audio_split.update(Message::UpdateParts("4".into()));
+    execute_tasks(task, &mut audio_split).await;
+
+    let mut ui = simulator(audio_split.view());
+    ui.click(DebugId::ButtonAnalyze.id()).unwrap();
+    for message in ui.into_messages() {
+        let task = audio_split.update(message);
+        execute_tasks(task, &mut audio_split).await;
+    }
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::InfoSplitPointsGenerated(3).id()).unwrap();
+}
+
+#[tokio::test]
+async fn equal_parts_invalid_input() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+
+    let task = audio_split.update(Message::SelectSplitMode(SplitMode::EqualParts));
+    execute_tasks(task, &mut audio_split).await;
+    let task = audio_split.update(Message::UpdateParts("2.5".into()));
+    execute_tasks(task, &mut audio_split).await;
+
+    let mut ui = simulator(audio_split.view());
+    ui.click(DebugId::ButtonAnalyze.id()).unwrap();
+    for message in ui.into_messages() {
+        let task = audio_split.update(message);
+        execute_tasks(task, &mut audio_split).await;
+    }
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::ErrorParseInt.id()).unwrap();
+}
+
+#[tokio::test]
+async fn track_boundaries() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+
+    let task = audio_split.update(Message::SelectSplitMode(SplitMode::TrackBoundaries));
+    execute_tasks(task, &mut audio_split).await;
+
+    let mut ui = simulator(audio_split.view());
+    ui.click(DebugId::ButtonAnalyze.id()).unwrap();
+    for message in ui.into_messages() {
+        let task = audio_split.update(message);
+        execute_tasks(task, &mut audio_split).await;
+    }
+    let mut ui = simulator(audio_split.view());
+    // a reading has no tracks, its strongest changes are proposed at least 30 s apart
+    ui.find(DebugId::InfoTrackBoundaries(3).id()).unwrap();
+}
@@ -0,0 +1,36 @@
+use crate::{execute_tasks, init};
+use audio_split::*;
+use iced_test::simulator;
+
+#[tokio::test]
+async fn levels_measured() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+
+    let mut ui = simulator(audio_split.view());
+    ui.click(DebugId::ButtonAnalyzeLevels.id()).unwrap();
+    for message in ui.into_messages() {
+        let task = audio_split.update(message);
+        execute_tasks(task, &mut audio_split).await;
+    }
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::InfoLevelsMeasured.id()).unwrap();
+}
+
+#[tokio::test]
+async fn levels_without_audio() {
+    let mut audio_split = init();
+
+    let mut ui = simulator(audio_split.view());
+    ui.click(DebugId::ButtonAnalyzeLevels.id()).unwrap();
+    for message in ui.into_messages() {
+        let task = audio_split.update(message);
+        execute_tasks(task, &mut audio_split).await;
+    }
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::WarningNoAudioLoaded.id()).unwrap();
+}
@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+use crate::{execute_tasks, init};
+use audio_split::*;
+use iced_test::simulator;
+
+#[tokio::test]
+async fn merge_with_next() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+
+    let task = audio_split.update(Message::ClickSplitPoint(Duration::from_secs_f32(22.10245)));
+    execute_tasks(task, &mut audio_split).await;
+    let task = audio_split.update(Message::Split);
+    execute_tasks(task, &mut audio_split).await;
+
+    let mut ui = simulator(audio_split.view());
+    ui.click(DebugId::ButtonMergeNext(0).id()).unwrap();
+    for message in ui.into_messages() {
+        let task = audio_split.update(message);
+        execute_tasks(task, &mut audio_split).await;
+    }
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::InfoSpansMerged(1).id()).unwrap();
+    assert!(ui.find(DebugId::ButtonDelete(1).id()).is_err());
+}
+
+#[tokio::test]
+async fn merge_selected_undo() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+
+    let task = audio_split.update(Message::ClickSplitPoint(Duration::from_secs_f32(22.10245)));
+    execute_tasks(task, &mut audio_split).await;
+    let task = audio_split.update(Message::Split);
+    execute_tasks(task, &mut audio_split).await;
+
+    let task = audio_split.update(Message::MergeSpans(vec![0, 1]));
+    execute_tasks(task, &mut audio_split).await;
+    {
+        let mut ui = simulator(audio_split.view());
+        assert!(ui.find(DebugId::ButtonDelete(1).id()).is_err());
+    }
+
+    let task = audio_split.update(Message::Undo);
+    execute_tasks(task, &mut audio_split).await;
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::ButtonDelete(1).id()).unwrap();
+}
+
+#[tokio::test]
+async fn merge_nothing_selected() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+
+    let mut ui = simulator(audio_split.view());
+    ui.click(DebugId::ButtonMergeSelected.id()).unwrap();
+    for message in ui.into_messages() {
+        let task = audio_split.update(message);
+        execute_tasks(task, &mut audio_split).await;
+    }
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::WarningNoAdjacentSpans.id()).unwrap();
+}
@@ -1,8 +1,21 @@
 mod analyze;
+mod analyze_range;
 mod audio_file;
+mod classify;
+mod delete;
+mod generate;
+mod levels;
+mod looping;
+mod markers;
+mod merge;
 mod play_pause;
+mod preview;
+mod selection;
+mod shortcuts;
 mod split;
 mod text_input;
+mod threshold;
+mod trim;
 use std::{
     sync::{Arc, atomic::AtomicBool},
     thread::{self, JoinHandle},
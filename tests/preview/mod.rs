@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use crate::{execute_tasks, init};
+use audio_split::*;
+use iced_test::simulator;
+
+#[tokio::test]
+async fn preview_split_point() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+
+    let task = audio_split.update(Message::PreviewSplitPoint(Duration::from_secs_f32(
+        22.10245,
+    )));
+    execute_tasks(task, &mut audio_split).await;
+
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::InfoPreviewCut.id()).unwrap();
+    ui.find(DebugId::ButtonPause.id()).unwrap();
+}
+
+#[tokio::test]
+async fn preview_duration_not_a_number() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+    let task = audio_split.update(Message::UpdatePreviewDuration("b".into()));
+    execute_tasks(task, &mut audio_split).await;
+
+    let task = audio_split.update(Message::PreviewSplitPoint(Duration::from_secs_f32(
+        22.10245,
+    )));
+    execute_tasks(task, &mut audio_split).await;
+
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::ErrorParseFloat.id()).unwrap();
+}
@@ -0,0 +1,58 @@
+use crate::{execute_tasks, init};
+use audio_split::*;
+use iced_test::simulator;
+
+async fn generate_parts(audio_split: &mut AudioSplit<crate::TestPlayer>) {
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, audio_split).await;
+
+    let task = audio_split.update(Message::SelectSplitMode(SplitMode::EqualParts));
+    execute_tasks(task, audio_split).await;
+    let task = audio_split.update(Message::UpdateParts("5".into()));
+    execute_tasks(task, audio_split).await;
+    let task = audio_split.update(Message::Analyze);
+    execute_tasks(task, audio_split).await;
+}
+
+#[tokio::test]
+async fn select_all_and_undo() {
+    let mut audio_split = init();
+    generate_parts(&mut audio_split).await;
+
+    let mut ui = simulator(audio_split.view());
+    ui.click(DebugId::ButtonSelectAll.id()).unwrap();
+    for message in ui.into_messages() {
+        let task = audio_split.update(message);
+        execute_tasks(task, &mut audio_split).await;
+    }
+    {
+        let mut ui = simulator(audio_split.view());
+        ui.find(DebugId::InfoSelection(4).id()).unwrap();
+    }
+
+    let task = audio_split.update(Message::Undo);
+    execute_tasks(task, &mut audio_split).await;
+    let task = audio_split.update(Message::BulkSelect(Selection::Invert));
+    execute_tasks(task, &mut audio_split).await;
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::InfoSelection(4).id()).unwrap();
+}
+
+#[tokio::test]
+async fn select_every_nth() {
+    let mut audio_split = init();
+    generate_parts(&mut audio_split).await;
+
+    let task = audio_split.update(Message::UpdateNth("2".into()));
+    execute_tasks(task, &mut audio_split).await;
+    let mut ui = simulator(audio_split.view());
+    ui.click(DebugId::ButtonSelectEveryNth.id()).unwrap();
+    for message in ui.into_messages() {
+        let task = audio_split.update(message);
+        execute_tasks(task, &mut audio_split).await;
+    }
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::InfoSelection(2).id()).unwrap();
+}
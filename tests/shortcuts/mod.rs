@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use crate::{execute_tasks, init};
+use audio_split::*;
+use iced::keyboard::{Key, Modifiers, key::Named};
+use iced_test::simulator;
+
+#[tokio::test]
+async fn help_overlay() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::KeyPressed(
+        Key::Named(Named::F1),
+        Modifiers::empty(),
+    ));
+    execute_tasks(task, &mut audio_split).await;
+    {
+        let mut ui = simulator(audio_split.view());
+        ui.find(DebugId::HelpOverlay.id()).unwrap();
+    }
+    let task = audio_split.update(Message::KeyPressed(
+        Key::Named(Named::Escape),
+        Modifiers::empty(),
+    ));
+    execute_tasks(task, &mut audio_split).await;
+    let mut ui = simulator(audio_split.view());
+    assert!(ui.find(DebugId::HelpOverlay.id()).is_err());
+}
+
+#[tokio::test]
+async fn split_shortcut() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+
+    let task = audio_split.update(Message::ClickSplitPoint(Duration::from_secs_f32(22.10245)));
+    execute_tasks(task, &mut audio_split).await;
+
+    let task = audio_split.update(Message::KeyPressed(
+        Key::Character("s".into()),
+        Modifiers::empty(),
+    ));
+    execute_tasks(task, &mut audio_split).await;
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::InfoSplits(1).id()).unwrap();
+}
+
+#[tokio::test]
+async fn undo_redo_split() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+
+    let task = audio_split.update(Message::ClickSplitPoint(Duration::from_secs_f32(22.10245)));
+    execute_tasks(task, &mut audio_split).await;
+    let task = audio_split.update(Message::Split);
+    execute_tasks(task, &mut audio_split).await;
+
+    let task = audio_split.update(Message::KeyPressed(
+        Key::Character("z".into()),
+        Modifiers::CTRL,
+    ));
+    execute_tasks(task, &mut audio_split).await;
+    {
+        let mut ui = simulator(audio_split.view());
+        assert!(ui.find(DebugId::ButtonDelete(1).id()).is_err());
+    }
+
+    let task = audio_split.update(Message::KeyPressed(
+        Key::Character("y".into()),
+        Modifiers::CTRL,
+    ));
+    execute_tasks(task, &mut audio_split).await;
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::ButtonDelete(1).id()).unwrap();
+}
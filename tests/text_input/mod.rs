@@ -67,3 +67,18 @@ async fn duration_not_negative() {
     let mut ui = simulator(audio_split.view());
     ui.find(DebugId::ErrorNegativeDuration.id()).unwrap();
 }
+
+#[tokio::test]
+async fn min_score_out_of_range() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+
+    let _ = audio_split.update(Message::UpdateMinScore("150".into()));
+    let _ = audio_split.update(Message::SelectAboveScore);
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::ErrorPercentOutOfRange.id()).unwrap();
+}
@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use crate::{execute_tasks, init};
+use audio_split::*;
+use iced_test::simulator;
+
+#[tokio::test]
+async fn auto_threshold() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+
+    let mut ui = simulator(audio_split.view());
+    ui.click(DebugId::ButtonAutoThreshold.id()).unwrap();
+    for message in ui.into_messages() {
+        let task = audio_split.update(message);
+        execute_tasks(task, &mut audio_split).await;
+    }
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::InfoThresholdEstimated.id()).unwrap();
+}
+
+#[tokio::test]
+async fn auto_threshold_no_audio() {
+    let mut audio_split = init();
+
+    let mut ui = simulator(audio_split.view());
+    ui.click(DebugId::ButtonAutoThreshold.id()).unwrap();
+    for message in ui.into_messages() {
+        let task = audio_split.update(message);
+        execute_tasks(task, &mut audio_split).await;
+    }
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::WarningNoAudioLoaded.id()).unwrap();
+}
+
+#[tokio::test]
+async fn live_analysis() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+    // 50 ms windows with two quiet passages of one second
+    let mut envelope = vec![-20.0; 400];
+    envelope[100..120].fill(-70.0);
+    envelope[200..220].fill(-50.0);
+    let envelope = Envelope::new(envelope, Duration::from_millis(50));
+    let task = audio_split.update(Message::EnvelopeLoaded(Ok(std::sync::Arc::new(envelope))));
+    execute_tasks(task, &mut audio_split).await;
+    let task = audio_split.update(Message::UpdateDuration("0.5".into()));
+    execute_tasks(task, &mut audio_split).await;
+
+    let task = audio_split.update(Message::SlideThreshold(-60.0));
+    execute_tasks(task, &mut audio_split).await;
+    let task = audio_split.update(Message::SlideThreshold(-40.0));
+    execute_tasks(task, &mut audio_split).await;
+    let task = audio_split.update(Message::ApplyLiveAnalysis);
+    execute_tasks(task, &mut audio_split).await;
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::InfoSplitPointsDetected(2).id()).unwrap();
+}
+
+#[tokio::test]
+async fn live_analysis_keeps_generated_points() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+    let mut envelope = vec![-20.0; 400];
+    envelope[100..120].fill(-70.0);
+    let envelope = Envelope::new(envelope, Duration::from_millis(50));
+    let task = audio_split.update(Message::EnvelopeLoaded(Ok(std::sync::Arc::new(envelope))));
+    execute_tasks(task, &mut audio_split).await;
+
+    let task = audio_split.update(Message::SelectSplitMode(SplitMode::EqualParts));
+    execute_tasks(task, &mut audio_split).await;
+    let task = audio_split.update(Message::Analyze);
+    execute_tasks(task, &mut audio_split).await;
+
+    let task = audio_split.update(Message::SlideThreshold(-60.0));
+    execute_tasks(task, &mut audio_split).await;
+    let task = audio_split.update(Message::ApplyLiveAnalysis);
+    execute_tasks(task, &mut audio_split).await;
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::InfoSplitPointsGenerated(3).id()).unwrap();
+}
+
+#[tokio::test]
+async fn live_analysis_typed_threshold() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+    let mut envelope = vec![-20.0; 400];
+    envelope[100..120].fill(-70.0);
+    let envelope = Envelope::new(envelope, Duration::from_millis(50));
+    let task = audio_split.update(Message::EnvelopeLoaded(Ok(std::sync::Arc::new(envelope))));
+    execute_tasks(task, &mut audio_split).await;
+    let task = audio_split.update(Message::UpdateThreshold("-".into()));
+    execute_tasks(task, &mut audio_split).await;
+    let task = audio_split.update(Message::UpdateDuration("0.5".into()));
+    execute_tasks(task, &mut audio_split).await;
+    let _ = audio_split.update(Message::UpdateMinScore("0".into()));
+    let _ = audio_split.update(Message::SelectAboveScore);
+    {
+        let mut ui = simulator(audio_split.view());
+        ui.find(DebugId::InfoSelectedAboveScore(0).id()).unwrap();
+    }
+
+    let task = audio_split.update(Message::UpdateThreshold("-60".into()));
+    execute_tasks(task, &mut audio_split).await;
+    let _ = audio_split.update(Message::SelectAboveScore);
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::InfoSelectedAboveScore(1).id()).unwrap();
+}
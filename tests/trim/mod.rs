@@ -0,0 +1,41 @@
+use crate::{execute_tasks, init};
+use audio_split::*;
+use iced_test::simulator;
+
+#[tokio::test]
+async fn trim_span_start() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+
+    let task = audio_split.update(Message::UpdateSpanStartInput(0, "5.0".into()));
+    execute_tasks(task, &mut audio_split).await;
+    let task = audio_split.update(Message::SubmitSpanRange(0));
+    execute_tasks(task, &mut audio_split).await;
+
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::ButtonRestore(1).id()).unwrap();
+    // the untouched end is not cut at its rounded input
+    assert!(ui.find(DebugId::ButtonRestore(2).id()).is_err());
+}
+
+#[tokio::test]
+async fn trim_span_end_not_a_number() {
+    let mut audio_split = init();
+
+    let task = audio_split.update(Message::AudioFilePathLoaded(Some(
+        "media/LibriVox_00.mp3".into(),
+    )));
+    execute_tasks(task, &mut audio_split).await;
+
+    let task = audio_split.update(Message::UpdateSpanEndInput(0, "b".into()));
+    execute_tasks(task, &mut audio_split).await;
+    let task = audio_split.update(Message::SubmitSpanRange(0));
+    execute_tasks(task, &mut audio_split).await;
+
+    let mut ui = simulator(audio_split.view());
+    ui.find(DebugId::ErrorParseFloat.id()).unwrap();
+}